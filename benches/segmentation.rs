@@ -0,0 +1,70 @@
+//! Runs the splitter/merger combinations of the report over `test_images/` with a growing number
+//! of workers, and prints the measurements as CSV.
+//!
+//! `cargo bench` runs every image, `cargo bench -- <filter>` only the images whose name contains
+//! the filter. For other combinations use the `bench` subcommand of the binary.
+use asymmetric_split_and_merge::prelude::*;
+
+const WORKERS: [usize; 3] = [1, 2, 4];
+
+fn main() -> Result<()> {
+    // cargo passes `--bench` to the benchmark binaries
+    let filter = std::env::args().skip(1).find(|x| !x.starts_with('-'));
+
+    let mut images = std::fs::read_dir("./test_images")?
+        .map(|entry| Ok(entry?.path()))
+        .collect::<Result<Vec<_>>>()?;
+    images.retain(|path| path.is_file() && ImageContainer::is_image_file(path));
+    images.sort();
+
+    let pool = WorkerPool::new(WORKERS[WORKERS.len() - 1])?;
+    let mut records = Vec::new();
+    for path in images {
+        let image_name = path
+            .file_name()
+            .map(|x| x.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        if filter.as_ref().is_some_and(|filter| !image_name.contains(filter.as_str())) {
+            continue;
+        }
+        let image = ImageContainer::new_from_file_color(&path.to_string_lossy())?;
+
+        for num_of_workers in WORKERS {
+            let case = |splitter: &str, merger: &str| BenchmarkCase {
+                image_name: image_name.clone(),
+                splitter_name: splitter.into(),
+                merger_name: merger.into(),
+                num_of_workers,
+            };
+            records.push(BenchmarkRecord::measure(
+                case("std", "std"),
+                &image,
+                splitter_traits::StdSplitter::new(3, 50.),
+                merger_traits::StdMerger::new(250.),
+                MergeStrategy::Greedy,
+                &pool,
+            )?);
+            records.push(BenchmarkRecord::measure(
+                case("max-delta", "color-based"),
+                &image,
+                splitter_traits::MaxDeltaSplitter::new(3, 50.),
+                merger_traits::ColorBasedMerger::new(70., 250.),
+                MergeStrategy::Greedy,
+                &pool,
+            )?);
+            records.push(BenchmarkRecord::measure(
+                case("max-delta+asymmetric", "color-based"),
+                &image,
+                splitter_traits::HeuristicAsymmetricSplitter::new(
+                    splitter_traits::MaxDeltaSplitter::new(3, 50.),
+                ),
+                merger_traits::ColorBasedMerger::new(70., 250.),
+                MergeStrategy::Greedy,
+                &pool,
+            )?);
+        }
+    }
+
+    print!("{}", benchmark_report(&records, BenchmarkFormat::Csv));
+    return Ok(());
+}
@@ -0,0 +1,75 @@
+use anyhow::Context;
+use std::fs;
+
+use asymmetric_split_and_merge::prelude::*;
+
+use crate::cli::{value_name, BenchArgs, BenchmarkFormatKind, Cli};
+
+/// run every combination of image, splitter, merger and number of workers, and write the report
+pub fn run_benchmarks(cli: &Cli, args: &BenchArgs) -> Result<()> {
+    let mut images = fs::read_dir(&args.images)
+        .with_context(|| format!("unable to read the directory {}", args.images))?
+        .map(|entry| Ok(entry?.path()))
+        .collect::<Result<Vec<_>>>()?;
+    images.retain(|path| path.is_file() && ImageContainer::is_image_file(path));
+    images.sort();
+
+    let splitters = if args.splitters.is_empty() {
+        vec![cli.splitter]
+    } else {
+        args.splitters.clone()
+    };
+    let mergers = if args.mergers.is_empty() {
+        vec![cli.merger]
+    } else {
+        args.mergers.clone()
+    };
+    let format = match args.format {
+        BenchmarkFormatKind::Csv => BenchmarkFormat::Csv,
+        BenchmarkFormatKind::Json => BenchmarkFormat::Json,
+    };
+
+    let pool = WorkerPool::new(args.workers.iter().copied().max().unwrap_or(1))?;
+    let mut records = Vec::new();
+    for path in images {
+        let image = ImageContainer::new_from_file_color(&path.to_string_lossy())?
+            .with_color_space(cli.get_color_space())?;
+        let image_name = path
+            .file_name()
+            .map(|x| x.to_string_lossy().into_owned())
+            .unwrap_or_default();
+
+        for splitter in &splitters {
+            for merger in &mergers {
+                for num_of_workers in &args.workers {
+                    for _ in 0..args.repetitions {
+                        let case = BenchmarkCase {
+                            image_name: image_name.clone(),
+                            splitter_name: cli.get_splitter_name(*splitter),
+                            merger_name: value_name(*merger),
+                            num_of_workers: *num_of_workers,
+                        };
+                        info!("running benchmark {case:?}");
+                        let record = BenchmarkRecord::measure(
+                            case,
+                            &image,
+                            cli.build_splitter_of_kind(*splitter),
+                            cli.build_merger_of_kind(*merger),
+                            cli.get_merge_strategy(),
+                            &pool,
+                        )?;
+                        records.push(record);
+                    }
+                }
+            }
+        }
+    }
+
+    let report = benchmark_report(&records, format);
+    match &args.report {
+        Some(path) => fs::write(path, report)
+            .with_context(|| format!("unable to write the report to {path}"))?,
+        None => print!("{report}"),
+    }
+    return Ok(());
+}
@@ -0,0 +1,127 @@
+use std::time::Duration;
+
+use crate::json::json_string;
+use crate::prelude::*;
+
+/// configuration of a single benchmark run, the names are only used in the reports
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BenchmarkCase {
+    pub image_name: String,
+    pub splitter_name: String,
+    pub merger_name: String,
+    pub num_of_workers: usize,
+}
+
+/// measurements of a single run of the split and merge algorithm, the timings are the ones in
+/// `statistics`
+#[derive(Debug, Clone)]
+pub struct BenchmarkRecord {
+    pub case: BenchmarkCase,
+    pub statistics: RunStatistics,
+    pub num_of_regions: usize,
+}
+
+impl BenchmarkRecord {
+    pub const CSV_HEADER: &'static str = "image,splitter,merger,workers,split_ms,initialization_ms,merge_ms,total_ms,splits,merge_rounds,regions";
+
+    /// run the split and merge algorithm once (with both phases on `case.num_of_workers` workers)
+    pub fn measure<S: SplitterTrait, M: MergerTrait>(
+        case: BenchmarkCase,
+        image: &ImageContainer,
+        splitter: S,
+        merger: M,
+        merge_strategy: MergeStrategy,
+        pool: &WorkerPool,
+    ) -> Result<Self> {
+        let logic = MainLogic::new(splitter, merger, logger_traits::NullLogger::new(), image, pool)
+            .with_merge_strategy(merge_strategy);
+        let logic = logic.execute_split(case.num_of_workers)?;
+        let logic = logic.execute_merge(case.num_of_workers)?;
+
+        return Ok(Self {
+            case,
+            statistics: *logic.get_run_statistics(),
+            num_of_regions: logic.get_result().regions.len(),
+        });
+    }
+
+    /// the record in the same order as `CSV_HEADER`
+    pub fn to_csv_row(&self) -> String {
+        return format!(
+            "{},{},{},{},{:.3},{:.3},{:.3},{:.3},{},{},{}",
+            csv_field(&self.case.image_name),
+            csv_field(&self.case.splitter_name),
+            csv_field(&self.case.merger_name),
+            self.case.num_of_workers,
+            as_millis(self.statistics.split_time),
+            as_millis(self.statistics.initialization_time),
+            as_millis(self.statistics.merge_time),
+            as_millis(self.total_time()),
+            self.statistics.splits,
+            self.statistics.merge_rounds,
+            self.num_of_regions,
+        );
+    }
+
+    pub fn to_json(&self) -> String {
+        return format!(
+            concat!(
+                "{{\"image\":{},\"splitter\":{},\"merger\":{},\"workers\":{},",
+                "\"split_ms\":{:.3},\"initialization_ms\":{:.3},\"merge_ms\":{:.3},\"total_ms\":{:.3},",
+                "\"splits\":{},\"merge_rounds\":{},\"regions\":{}}}"
+            ),
+            json_string(&self.case.image_name),
+            json_string(&self.case.splitter_name),
+            json_string(&self.case.merger_name),
+            self.case.num_of_workers,
+            as_millis(self.statistics.split_time),
+            as_millis(self.statistics.initialization_time),
+            as_millis(self.statistics.merge_time),
+            as_millis(self.total_time()),
+            self.statistics.splits,
+            self.statistics.merge_rounds,
+            self.num_of_regions,
+        );
+    }
+
+    /// wall time of the whole run
+    pub fn total_time(&self) -> Duration {
+        let statistics = &self.statistics;
+        return statistics.split_time + statistics.initialization_time + statistics.merge_time;
+    }
+}
+
+/// report format of the benchmarks
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum BenchmarkFormat {
+    #[default]
+    Csv,
+    Json,
+}
+
+/// serialize the records of a benchmark, one row (or json object) for each run
+pub fn benchmark_report(records: &[BenchmarkRecord], format: BenchmarkFormat) -> String {
+    return match format {
+        BenchmarkFormat::Csv => {
+            let mut rows = vec![BenchmarkRecord::CSV_HEADER.to_string()];
+            rows.extend(records.iter().map(|r| r.to_csv_row()));
+            rows.join("\n") + "\n"
+        }
+        BenchmarkFormat::Json => {
+            let records = records.iter().map(|r| r.to_json()).collect::<Vec<_>>();
+            format!("[{}]\n", records.join(","))
+        }
+    };
+}
+
+/// quote a field that contains the separator, a quote or a line break
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        return format!("\"{}\"", field.replace('"', "\"\""));
+    }
+    return field.to_string();
+}
+
+fn as_millis(duration: Duration) -> f64 {
+    return duration.as_secs_f64() * 1000.;
+}
@@ -0,0 +1,2 @@
+mod benchmark_record;
+pub use benchmark_record::*;
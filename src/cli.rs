@@ -0,0 +1,475 @@
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use anyhow::Context;
+use log::{debug, LevelFilter};
+use std::time::Duration;
+
+use asymmetric_split_and_merge::prelude::*;
+
+#[derive(ValueEnum, Debug, Clone, Copy)]
+pub enum SplitterKind {
+    Blind,
+    Std,
+    HueStd,
+    MaxDelta,
+    Texture,
+}
+
+#[derive(ValueEnum, Debug, Clone, Copy)]
+pub enum MergerKind {
+    Blind,
+    Std,
+    ColorBased,
+    Texture,
+}
+
+#[derive(ValueEnum, Debug, Clone, Copy)]
+pub enum ColorSpaceKind {
+    Bgr,
+    Lab,
+    Hsv,
+    YCrCb,
+    Gray,
+}
+
+#[derive(ValueEnum, Debug, Clone, Copy)]
+pub enum GraphFormatKind {
+    Dot,
+    Graphml,
+    Json,
+}
+
+#[derive(ValueEnum, Debug, Clone, Copy)]
+pub enum PolygonFormatKind {
+    Geojson,
+    Svg,
+}
+
+#[derive(ValueEnum, Debug, Clone, Copy)]
+pub enum CocoSegmentationKind {
+    Rle,
+    Polygon,
+}
+
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ResultOutputKind {
+    Labels,
+    Overlay,
+    Boundaries,
+    Masks,
+}
+
+#[derive(ValueEnum, Debug, Clone, Copy)]
+pub enum OverlayKindArg {
+    FalseColor,
+    MeanColor,
+}
+
+#[derive(ValueEnum, Debug, Clone, Copy)]
+pub enum MergeStrategyKind {
+    Greedy,
+    BestFirst,
+}
+
+#[derive(ValueEnum, Debug, Clone, Copy)]
+pub enum BenchmarkFormatKind {
+    Csv,
+    Json,
+}
+
+#[derive(ValueEnum, Debug, Clone, Copy)]
+pub enum LoggerKind {
+    Null,
+    OnScreen,
+    OnDisk,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum Command {
+    /// segment every image of a directory (with the options given before the subcommand) and
+    /// compare it with its ground truth
+    Evaluate(EvaluateArgs),
+    /// measure the time of each phase for several combinations of splitters, mergers and number
+    /// of workers over a directory of images
+    Bench(BenchArgs),
+}
+
+#[derive(Args, Debug)]
+pub struct BenchArgs {
+    /// directory with the images to segment
+    #[arg(long, default_value = "./test_images")]
+    pub images: String,
+
+    /// splitters to benchmark (comma separated), the one selected with `--splitter` by default
+    #[arg(long, value_enum, value_delimiter = ',')]
+    pub splitters: Vec<SplitterKind>,
+
+    /// mergers to benchmark (comma separated), the one selected with `--merger` by default
+    #[arg(long, value_enum, value_delimiter = ',')]
+    pub mergers: Vec<MergerKind>,
+
+    /// numbers of workers to benchmark (comma separated), used for both phases
+    #[arg(long, value_delimiter = ',', default_values_t = [1, 2, 4])]
+    pub workers: Vec<usize>,
+
+    /// number of times each configuration is run
+    #[arg(long, default_value_t = 1)]
+    pub repetitions: usize,
+
+    /// format of the report
+    #[arg(long, value_enum, default_value_t = BenchmarkFormatKind::Csv)]
+    pub format: BenchmarkFormatKind,
+
+    /// if set, the report is written to this path instead of the standard output
+    #[arg(long)]
+    pub report: Option<String>,
+}
+
+#[derive(Args, Debug)]
+pub struct EvaluateArgs {
+    /// directory with the images to segment
+    pub images: String,
+
+    /// directory with the ground truth label images, each one must have the same name of its
+    /// image (extension excluded)
+    pub ground_truth: String,
+
+    /// maximum distance (in pixels) between two matching boundaries
+    #[arg(long, default_value_t = 2)]
+    pub boundary_tolerance: usize,
+
+    /// if set, the metrics of every image are written to this csv file
+    #[arg(long)]
+    pub report: Option<String>,
+}
+
+/// multithreaded split and merge segmentation of an image
+#[derive(Parser, Debug)]
+#[command(version, about, subcommand_negates_reqs = true)]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+
+    /// path of the image to segment
+    #[arg(short, long, required = true)]
+    pub input: Option<String>,
+
+    /// path of the video generated by the `on-disk` logger
+    #[arg(short, long, default_value = "./out.mp4")]
+    pub output: String,
+
+    /// color space used by splitters and mergers (with `lab` the `color-based` merger uses the
+    /// ΔE distance, with `hsv` it compares the hues around the hue circle)
+    #[arg(long, value_enum, default_value_t = ColorSpaceKind::Bgr)]
+    pub color_space: ColorSpaceKind,
+
+    /// splitting strategy
+    #[arg(long, value_enum, default_value_t = SplitterKind::MaxDelta)]
+    pub splitter: SplitterKind,
+
+    /// wrap the splitter in a `HeuristicAsymmetricSplitter`
+    #[arg(long, conflicts_with_all = ["variance_cut", "quad"])]
+    pub asymmetric: bool,
+
+    /// wrap the splitter in a `VarianceSplitter`, that cuts where the variance of the two
+    /// halves is minimal
+    #[arg(long, conflicts_with = "quad")]
+    pub variance_cut: bool,
+
+    /// wrap the splitter in a `QuadSplitter`, that splits the areas in four quadrants
+    #[arg(long)]
+    pub quad: bool,
+
+    /// areas smaller than this size are never split
+    #[arg(long, default_value_t = 3, value_parser = clap::value_parser!(i32).range(2..))]
+    pub min_split_size: i32,
+
+    /// threshold used by the `std`, `hue-std` and `max-delta` splitters
+    /// (and color threshold of the `texture` splitter)
+    #[arg(long, default_value_t = 50.)]
+    pub split_threshold: f64,
+
+    /// texture distance threshold (from 0 to 1) used by the `texture` splitter and merger
+    #[arg(long, default_value_t = 0.1)]
+    pub texture_threshold: f64,
+
+    /// merging strategy
+    #[arg(long, value_enum, default_value_t = MergerKind::ColorBased)]
+    pub merger: MergerKind,
+
+    /// order in which the couples of neighbor areas are evaluated
+    #[arg(long, value_enum, default_value_t = MergeStrategyKind::Greedy)]
+    pub merge_strategy: MergeStrategyKind,
+
+    /// color distance threshold used by the `color-based` and `texture` mergers
+    #[arg(long, default_value_t = 70.)]
+    pub merge_color_threshold: f64,
+
+    /// standard deviation threshold used by the `std` and `color-based` mergers
+    #[arg(long, default_value_t = 250.)]
+    pub merge_std_threshold: f64,
+
+    /// if set, the region adjacency graph of the final segmentation is written to this path
+    #[arg(long)]
+    pub graph_output: Option<String>,
+
+    /// file format of the region adjacency graph
+    #[arg(long, value_enum, default_value_t = GraphFormatKind::Dot)]
+    pub graph_format: GraphFormatKind,
+
+    /// if set, the regions of the final segmentation are vectorized and written to this path
+    #[arg(long)]
+    pub polygon_output: Option<String>,
+
+    /// file format of the polygons
+    #[arg(long, value_enum, default_value_t = PolygonFormatKind::Geojson)]
+    pub polygon_format: PolygonFormatKind,
+
+    /// maximum distance (in pixels) between the simplified polygons and the borders of the
+    /// regions, 0 disables the simplification
+    #[arg(long, default_value_t = 1.)]
+    pub polygon_tolerance: f64,
+
+    /// if set, the regions of the final segmentation are written to this path as COCO annotations
+    #[arg(long)]
+    pub coco_output: Option<String>,
+
+    /// how the masks of the regions are stored in the COCO annotations (the polygons are
+    /// simplified with `--polygon-tolerance`)
+    #[arg(long, value_enum, default_value_t = CocoSegmentationKind::Rle)]
+    pub coco_segmentation: CocoSegmentationKind,
+
+    /// if set, the outputs of the segmentation are written in this directory
+    #[arg(long)]
+    pub result_dir: Option<String>,
+
+    /// outputs written in the result directory (comma separated)
+    #[arg(
+        long,
+        value_enum,
+        value_delimiter = ',',
+        default_values_t = [ResultOutputKind::Labels, ResultOutputKind::Overlay, ResultOutputKind::Boundaries]
+    )]
+    pub result_outputs: Vec<ResultOutputKind>,
+
+    /// how the regions are colored in the overlay
+    #[arg(long, value_enum, default_value_t = OverlayKindArg::FalseColor)]
+    pub overlay: OverlayKindArg,
+
+    /// logger used to visualize the process
+    #[arg(long, value_enum, default_value_t = LoggerKind::OnDisk)]
+    pub logger: LoggerKind,
+
+    /// number of worker threads used in the split phase
+    #[arg(long, default_value_t = 10)]
+    pub split_workers: usize,
+
+    /// number of worker threads used in the merge phase
+    #[arg(long, default_value_t = 10)]
+    pub merge_workers: usize,
+
+    /// stop the segmentation after the given number of seconds, and keep the partial result
+    #[arg(long)]
+    pub time_limit: Option<f64>,
+
+    /// seed of the random colors of the loggers and of the overlay, runs with the same seed
+    /// give the same outputs
+    #[arg(long, default_value_t = DEFAULT_SEED)]
+    pub seed: u64,
+
+    /// verbosity of the log messages (off, error, warn, info, debug, trace)
+    #[arg(long, default_value_t = LevelFilter::Info)]
+    pub log_level: LevelFilter,
+}
+
+impl Cli {
+    pub fn build_splitter(&self) -> Box<dyn SplitterTrait> {
+        return self.build_splitter_of_kind(self.splitter);
+    }
+
+    /// build a splitter of any kind, with the parameters and wrappers of the command line
+    pub fn build_splitter_of_kind(&self, kind: SplitterKind) -> Box<dyn SplitterTrait> {
+        let min = self.min_split_size;
+        let threshold = self.split_threshold;
+        let splitter: Box<dyn SplitterTrait> = match kind {
+            SplitterKind::Blind => Box::new(splitter_traits::BlindSplitter::new(min)),
+            SplitterKind::Std => Box::new(splitter_traits::StdSplitter::new(min, threshold)),
+            SplitterKind::HueStd => Box::new(splitter_traits::HueStdSplitter::new(min, threshold)),
+            SplitterKind::MaxDelta => {
+                Box::new(splitter_traits::MaxDeltaSplitter::new(min, threshold))
+            }
+            SplitterKind::Texture => Box::new(splitter_traits::TextureSplitter::new(
+                min,
+                self.texture_threshold,
+                threshold,
+            )),
+        };
+        if self.asymmetric {
+            return Box::new(splitter_traits::HeuristicAsymmetricSplitter::new(splitter));
+        }
+        if self.variance_cut {
+            return Box::new(splitter_traits::VarianceSplitter::new(splitter));
+        }
+        if self.quad {
+            return Box::new(splitter_traits::QuadSplitter::new(splitter));
+        }
+        return splitter;
+    }
+
+    pub fn build_merger(&self) -> Box<dyn MergerTrait> {
+        return self.build_merger_of_kind(self.merger);
+    }
+
+    /// build a merger of any kind, with the parameters of the command line
+    pub fn build_merger_of_kind(&self, kind: MergerKind) -> Box<dyn MergerTrait> {
+        return match kind {
+            MergerKind::Blind => Box::new(merger_traits::BlindMerger::new()),
+            MergerKind::Std => Box::new(merger_traits::StdMerger::new(self.merge_std_threshold)),
+            MergerKind::ColorBased => {
+                let color_distance = match self.color_space {
+                    ColorSpaceKind::Lab => merger_traits::ColorDistance::DeltaE,
+                    ColorSpaceKind::Hsv => merger_traits::ColorDistance::Hsv,
+                    _ => merger_traits::ColorDistance::Euclidean,
+                };
+                Box::new(
+                    merger_traits::ColorBasedMerger::new(
+                        self.merge_color_threshold,
+                        self.merge_std_threshold,
+                    )
+                    .with_color_distance(color_distance),
+                )
+            }
+            MergerKind::Texture => Box::new(merger_traits::TextureMerger::new(
+                self.texture_threshold,
+                self.merge_color_threshold,
+            )),
+        };
+    }
+
+    pub fn get_color_space(&self) -> ColorSpace {
+        return match self.color_space {
+            ColorSpaceKind::Bgr => ColorSpace::Bgr,
+            ColorSpaceKind::Lab => ColorSpace::Lab,
+            ColorSpaceKind::Hsv => ColorSpace::Hsv,
+            ColorSpaceKind::YCrCb => ColorSpace::YCrCb,
+            ColorSpaceKind::Gray => ColorSpace::Gray,
+        };
+    }
+
+    /// pool with enough workers for both the phases of `segment`
+    pub fn build_pool(&self) -> Result<WorkerPool> {
+        return WorkerPool::new(self.split_workers.max(self.merge_workers));
+    }
+
+    /// run the split and merge algorithm with the options of the command line
+    pub fn segment<L: LoggerTrait>(
+        &self,
+        image: &ImageContainer,
+        logger: L,
+        pool: &WorkerPool,
+    ) -> Result<SegmentationResult> {
+        let mut cancellation_token = CancellationToken::new();
+        if let Some(time_limit) = self.time_limit {
+            let time_limit = Duration::try_from_secs_f64(time_limit)
+                .context("the time limit must be a positive number of seconds")?;
+            cancellation_token = cancellation_token.with_time_limit(time_limit);
+        }
+
+        let logic = MainLogic::new(self.build_splitter(), self.build_merger(), logger, image, pool)
+            .with_merge_strategy(self.get_merge_strategy())
+            .with_seed(self.seed)
+            .with_cancellation_token(cancellation_token)
+            .with_progress_callback(|progress| debug!("progress: {progress:?}"));
+        let logic = logic.execute_split(self.split_workers)?;
+        let logic = logic.execute_merge(self.merge_workers)?;
+        let (result, statistics) = logic.into_result_and_statistics();
+        info!("run statistics: {statistics:?}");
+        if result.is_partial {
+            warn!("the time limit has been reached, the segmentation is partial");
+        }
+        return Ok(result);
+    }
+
+    /// name of a splitter kind, including the wrapper selected on the command line
+    pub fn get_splitter_name(&self, kind: SplitterKind) -> String {
+        let mut name = value_name(kind);
+        if self.asymmetric {
+            name += "+asymmetric";
+        }
+        if self.variance_cut {
+            name += "+variance-cut";
+        }
+        if self.quad {
+            name += "+quad";
+        }
+        return name;
+    }
+
+    pub fn get_graph_format(&self) -> GraphFormat {
+        return match self.graph_format {
+            GraphFormatKind::Dot => GraphFormat::Dot,
+            GraphFormatKind::Graphml => GraphFormat::GraphMl,
+            GraphFormatKind::Json => GraphFormat::Json,
+        };
+    }
+
+    pub fn get_polygon_format(&self) -> PolygonFormat {
+        return match self.polygon_format {
+            PolygonFormatKind::Geojson => PolygonFormat::GeoJson,
+            PolygonFormatKind::Svg => PolygonFormat::Svg,
+        };
+    }
+
+    pub fn get_coco_segmentation(&self) -> CocoSegmentation {
+        return match self.coco_segmentation {
+            CocoSegmentationKind::Rle => CocoSegmentation::Rle,
+            CocoSegmentationKind::Polygon => CocoSegmentation::Polygon {
+                tolerance: self.polygon_tolerance,
+            },
+        };
+    }
+
+    pub fn build_result_writer(&self) -> Option<ResultWriter> {
+        let result_dir = self.result_dir.as_ref()?;
+        let overlay = match self.overlay {
+            OverlayKindArg::FalseColor => OverlayKind::FalseColor,
+            OverlayKindArg::MeanColor => OverlayKind::MeanColor,
+        };
+        let enabled = |output| self.result_outputs.contains(&output);
+        return Some(
+            ResultWriter::new(result_dir)
+                .with_labels(enabled(ResultOutputKind::Labels))
+                .with_overlay(enabled(ResultOutputKind::Overlay).then_some(overlay))
+                .with_boundaries(enabled(ResultOutputKind::Boundaries))
+                .with_masks(enabled(ResultOutputKind::Masks)),
+        );
+    }
+
+    pub fn get_merge_strategy(&self) -> MergeStrategy {
+        return match self.merge_strategy {
+            MergeStrategyKind::Greedy => MergeStrategy::Greedy,
+            MergeStrategyKind::BestFirst => MergeStrategy::BestFirst,
+        };
+    }
+
+    pub fn build_logger(&self, image: &ImageContainer) -> Result<Box<dyn LoggerTrait>> {
+        return Ok(match self.logger {
+            LoggerKind::Null => Box::new(logger_traits::NullLogger::new()),
+            LoggerKind::OnScreen => Box::new(logger_traits::OnScreenLogger::new(
+                image.get_original_image().clone(),
+                "log".into(),
+            )?),
+            LoggerKind::OnDisk => Box::new(logger_traits::OnDiskLogger::new(
+                image.get_original_image().clone(),
+                &self.output,
+            )?),
+        });
+    }
+}
+
+/// the name of a value on the command line
+pub fn value_name(value: impl ValueEnum) -> String {
+    return value
+        .to_possible_value()
+        .map(|x| x.get_name().to_string())
+        .unwrap_or_default();
+}
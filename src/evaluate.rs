@@ -0,0 +1,71 @@
+use anyhow::Context;
+use std::{collections::HashMap, fs, path::PathBuf};
+
+use asymmetric_split_and_merge::prelude::*;
+
+use crate::cli::{Cli, EvaluateArgs};
+
+/// image files of a directory, indexed by their name without extension
+fn files_by_stem(dir: &str) -> Result<HashMap<String, PathBuf>> {
+    let mut files = HashMap::new();
+    for entry in fs::read_dir(dir).with_context(|| format!("unable to read the directory {dir}"))? {
+        let path = entry?.path();
+        if !path.is_file() {
+            continue;
+        }
+        if !ImageContainer::is_image_file(&path) {
+            warn!("{} is not an image, skipping it", path.display());
+            continue;
+        }
+        if let Some(stem) = path.file_stem() {
+            files.insert(stem.to_string_lossy().into_owned(), path);
+        }
+    }
+    return Ok(files);
+}
+
+/// segment every image that has a ground truth, and log the metrics of each image and their mean
+pub fn evaluate_directory(cli: &Cli, args: &EvaluateArgs, pool: &WorkerPool) -> Result<()> {
+    let mut images = files_by_stem(&args.images)?.into_iter().collect::<Vec<_>>();
+    images.sort();
+    let ground_truths = files_by_stem(&args.ground_truth)?;
+
+    let mut csv = vec![format!("image,{}", EvaluationMetrics::CSV_HEADER)];
+    let mut all_metrics = Vec::new();
+    for (stem, image_path) in images {
+        let ground_truth_path = match ground_truths.get(&stem) {
+            Some(path) => path,
+            None => {
+                warn!("no ground truth found for {}, skipping it", image_path.display());
+                continue;
+            }
+        };
+
+        let image = ImageContainer::new_from_file_color(&image_path.to_string_lossy())?
+            .with_color_space(cli.get_color_space())?;
+        let result = cli
+            .segment(&image, logger_traits::NullLogger::new(), pool)
+            .with_context(|| format!("segmentation of {} has failed", image_path.display()))?;
+        let ground_truth = read_label_image(&ground_truth_path.to_string_lossy())?;
+        let metrics = EvaluationMetrics::compute(&result.labels, &[ground_truth], args.boundary_tolerance)
+            .with_context(|| format!("evaluation of {} has failed", image_path.display()))?;
+
+        info!("{stem}: {} regions, {metrics:?}", result.regions.len());
+        csv.push(format!("{stem},{}", metrics.to_csv_row()));
+        all_metrics.push(metrics);
+    }
+
+    if all_metrics.is_empty() {
+        return Err(anyhow!("no image with a ground truth has been found"));
+    }
+    let mean = EvaluationMetrics::mean(&all_metrics);
+    info!("mean over {} images: {mean:?}", all_metrics.len());
+    csv.push(format!("mean,{}", mean.to_csv_row()));
+
+    if let Some(path) = &args.report {
+        fs::write(path, csv.join("\n") + "\n")
+            .with_context(|| format!("unable to write the report to {path}"))?;
+        info!("evaluation report written to {path}");
+    }
+    return Ok(());
+}
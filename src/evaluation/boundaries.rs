@@ -0,0 +1,53 @@
+/// pixels that have a different label than their right or bottom neighbor
+pub fn boundary_mask(labels: &[i32], width: usize) -> Vec<bool> {
+    return (0..labels.len())
+        .map(|i| {
+            let right_differs = (i + 1) % width != 0 && labels[i] != labels[i + 1];
+            let bottom_differs = i + width < labels.len() && labels[i] != labels[i + width];
+            right_differs || bottom_differs
+        })
+        .collect();
+}
+
+/// dilate the mask with a square of side `2 * tolerance + 1`, so that every pixel is set if
+/// there is a set pixel at a distance (along x and y) of at most `tolerance`
+pub fn dilate_mask(mask: &[bool], width: usize, tolerance: usize) -> Vec<bool> {
+    if tolerance == 0 || mask.is_empty() {
+        return mask.to_vec();
+    }
+    let height = mask.len() / width;
+
+    // the square is separable in a horizontal and a vertical pass
+    let mut horizontal = vec![false; mask.len()];
+    for y in 0..height {
+        for x in 0..width {
+            let start = x.saturating_sub(tolerance);
+            let end = usize::min(x + tolerance, width - 1);
+            horizontal[y * width + x] = (start..=end).any(|x| mask[y * width + x]);
+        }
+    }
+    let mut dilated = vec![false; mask.len()];
+    for y in 0..height {
+        for x in 0..width {
+            let start = y.saturating_sub(tolerance);
+            let end = usize::min(y + tolerance, height - 1);
+            dilated[y * width + x] = (start..=end).any(|y| horizontal[y * width + x]);
+        }
+    }
+    return dilated;
+}
+
+/// fraction of the boundary pixels of `reference` that have a boundary pixel of `other` within
+/// the tolerance, 1 if `reference` has no boundaries
+pub fn boundary_match_ratio(reference: &[bool], other_dilated: &[bool]) -> f64 {
+    let total = reference.iter().filter(|x| **x).count();
+    if total == 0 {
+        return 1.;
+    }
+    let matched = reference
+        .iter()
+        .zip(other_dilated)
+        .filter(|(r, o)| **r && **o)
+        .count();
+    return matched as f64 / total as f64;
+}
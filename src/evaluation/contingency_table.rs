@@ -0,0 +1,91 @@
+use std::collections::{BTreeMap, HashMap};
+
+/// number of pixels in common between every region of a segmentation and every region of a
+/// ground truth, all the overlap based metrics are computed from it.
+/// The maps are ordered so that the floating point sums are always done in the same order
+#[derive(Debug, Default)]
+pub struct ContingencyTable {
+    pub pixel_count: usize,
+    /// pixels in common for each couple (segmentation label, ground truth label)
+    pub overlaps: BTreeMap<(i32, i32), usize>,
+    pub segmentation_sizes: BTreeMap<i32, usize>,
+    pub ground_truth_sizes: BTreeMap<i32, usize>,
+}
+
+impl ContingencyTable {
+    pub fn new(segmentation: &[i32], ground_truth: &[i32]) -> Self {
+        let mut table = Self::default();
+        for (s, g) in segmentation.iter().zip(ground_truth) {
+            *table.overlaps.entry((*s, *g)).or_insert(0) += 1;
+            *table.segmentation_sizes.entry(*s).or_insert(0) += 1;
+            *table.ground_truth_sizes.entry(*g).or_insert(0) += 1;
+            table.pixel_count += 1;
+        }
+        return table;
+    }
+
+    /// fraction of the pixels that are correctly labeled when every region of the segmentation is
+    /// assigned to the ground truth region it overlaps the most
+    pub fn achievable_segmentation_accuracy(&self) -> f64 {
+        let mut best_overlaps = HashMap::new();
+        for ((s, _), overlap) in &self.overlaps {
+            let best = best_overlaps.entry(*s).or_insert(0);
+            *best = usize::max(*best, *overlap);
+        }
+        return best_overlaps.values().sum::<usize>() as f64 / self.pixel_count as f64;
+    }
+
+    /// for every region of the segmentation that overlaps a ground truth region, the smallest
+    /// between the part inside and the part outside of the ground truth region (the version of
+    /// Neubert and Protzel, that doesn't penalize small overlaps on the borders)
+    pub fn undersegmentation_error(&self) -> f64 {
+        let leakage = self
+            .overlaps
+            .iter()
+            .map(|((s, _), overlap)| usize::min(*overlap, self.segmentation_sizes[s] - overlap))
+            .sum::<usize>();
+        return leakage as f64 / self.pixel_count as f64;
+    }
+
+    /// `H(S|G) + H(G|S)` in nats, 0 when the two segmentations are identical
+    pub fn variation_of_information(&self) -> f64 {
+        let n = self.pixel_count as f64;
+        let entropy = |sizes: &BTreeMap<i32, usize>| {
+            sizes
+                .values()
+                .map(|x| *x as f64 / n)
+                .map(|p| -p * p.ln())
+                .sum::<f64>()
+        };
+        let mutual_information = self
+            .overlaps
+            .iter()
+            .map(|((s, g), overlap)| {
+                let p = *overlap as f64 / n;
+                let p_s = self.segmentation_sizes[s] as f64 / n;
+                let p_g = self.ground_truth_sizes[g] as f64 / n;
+                p * (p / (p_s * p_g)).ln()
+            })
+            .sum::<f64>();
+        let vi = entropy(&self.segmentation_sizes) + entropy(&self.ground_truth_sizes)
+            - 2. * mutual_information;
+        // rounding errors could make it slightly negative
+        return vi.max(0.);
+    }
+
+    /// fraction of the couples of pixels on which the two segmentations agree (both in the same
+    /// region or both in different regions)
+    pub fn rand_index(&self) -> f64 {
+        let pairs = |x: usize| (x as f64) * (x as f64 - 1.) / 2.;
+        let total_pairs = pairs(self.pixel_count);
+        if total_pairs == 0. {
+            return 1.;
+        }
+        let same_in_segmentation = self.segmentation_sizes.values().map(|x| pairs(*x)).sum::<f64>();
+        let same_in_ground_truth = self.ground_truth_sizes.values().map(|x| pairs(*x)).sum::<f64>();
+        let same_in_both = self.overlaps.values().map(|x| pairs(*x)).sum::<f64>();
+
+        let disagreements = same_in_segmentation + same_in_ground_truth - 2. * same_in_both;
+        return 1. - disagreements / total_pairs;
+    }
+}
@@ -0,0 +1,126 @@
+use anyhow::Context;
+use opencv::core::{Vec3i, CV_32S};
+use opencv::imgcodecs::{imread, IMREAD_UNCHANGED};
+
+use super::*;
+
+/// quality of a segmentation compared to the ground truth segmentations of the same image
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct EvaluationMetrics {
+    /// fraction of the ground truth boundaries found by the segmentation (higher is better)
+    pub boundary_recall: f64,
+    /// fraction of the boundaries of the segmentation that are ground truth boundaries
+    /// (higher is better)
+    pub boundary_precision: f64,
+    /// fraction of the pixels that leak out of the ground truth regions (lower is better)
+    pub undersegmentation_error: f64,
+    /// upper bound of the accuracy that can be reached labeling the regions (higher is better)
+    pub achievable_segmentation_accuracy: f64,
+    /// in nats (lower is better)
+    pub variation_of_information: f64,
+    /// (higher is better)
+    pub probabilistic_rand_index: f64,
+}
+
+impl EvaluationMetrics {
+    pub const CSV_HEADER: &'static str = "boundary_recall,boundary_precision,undersegmentation_error,achievable_segmentation_accuracy,variation_of_information,probabilistic_rand_index";
+
+    /// compare a label image (like `SegmentationResult::labels`) with one or more ground truth
+    /// label images. With more than one ground truth the metrics are averaged, which for the
+    /// rand index gives the probabilistic rand index.
+    /// Two boundary pixels match if they are at most `boundary_tolerance` pixels apart
+    pub fn compute(segmentation: &Mat, ground_truths: &[Mat], boundary_tolerance: usize) -> Result<Self> {
+        if ground_truths.is_empty() {
+            return Err(anyhow!("at least one ground truth is needed"));
+        }
+        let width = segmentation.cols() as usize;
+        let segmentation_labels = labels_to_vec(segmentation).context("invalid segmentation")?;
+        let segmentation_boundaries = boundary_mask(&segmentation_labels, width);
+        let segmentation_boundaries_dilated =
+            dilate_mask(&segmentation_boundaries, width, boundary_tolerance);
+
+        let mut metrics = Vec::with_capacity(ground_truths.len());
+        for ground_truth in ground_truths {
+            if ground_truth.size()? != segmentation.size()? {
+                return Err(anyhow!("the ground truth and the segmentation have different sizes"));
+            }
+            let ground_truth_labels = labels_to_vec(ground_truth).context("invalid ground truth")?;
+            let ground_truth_boundaries = boundary_mask(&ground_truth_labels, width);
+            let ground_truth_boundaries_dilated =
+                dilate_mask(&ground_truth_boundaries, width, boundary_tolerance);
+
+            let table = ContingencyTable::new(&segmentation_labels, &ground_truth_labels);
+            metrics.push(Self {
+                boundary_recall: boundary_match_ratio(
+                    &ground_truth_boundaries,
+                    &segmentation_boundaries_dilated,
+                ),
+                boundary_precision: boundary_match_ratio(
+                    &segmentation_boundaries,
+                    &ground_truth_boundaries_dilated,
+                ),
+                undersegmentation_error: table.undersegmentation_error(),
+                achievable_segmentation_accuracy: table.achievable_segmentation_accuracy(),
+                variation_of_information: table.variation_of_information(),
+                probabilistic_rand_index: table.rand_index(),
+            });
+        }
+        return Ok(Self::mean(&metrics));
+    }
+
+    /// average of the metrics (for example of all the images of a dataset)
+    pub fn mean(metrics: &[Self]) -> Self {
+        if metrics.is_empty() {
+            return Self::default();
+        }
+        let n = metrics.len() as f64;
+        let mean = |f: fn(&Self) -> f64| metrics.iter().map(f).sum::<f64>() / n;
+        return Self {
+            boundary_recall: mean(|m| m.boundary_recall),
+            boundary_precision: mean(|m| m.boundary_precision),
+            undersegmentation_error: mean(|m| m.undersegmentation_error),
+            achievable_segmentation_accuracy: mean(|m| m.achievable_segmentation_accuracy),
+            variation_of_information: mean(|m| m.variation_of_information),
+            probabilistic_rand_index: mean(|m| m.probabilistic_rand_index),
+        };
+    }
+
+    /// the metrics in the same order as `CSV_HEADER`
+    pub fn to_csv_row(&self) -> String {
+        return [
+            self.boundary_recall,
+            self.boundary_precision,
+            self.undersegmentation_error,
+            self.achievable_segmentation_accuracy,
+            self.variation_of_information,
+            self.probabilistic_rand_index,
+        ]
+        .map(|x| x.to_string())
+        .join(",");
+    }
+}
+
+/// read a ground truth label image without any conversion (so that 16 bit labels are preserved)
+pub fn read_label_image(path: &str) -> Result<Mat> {
+    let mat = imread(path, IMREAD_UNCHANGED)?;
+    if mat.empty() {
+        return Err(anyhow!("unable to read the label image at {path}"));
+    }
+    return Ok(mat);
+}
+
+/// the labels of a single channel image (of any depth), or of a color coded image where every
+/// color is a different region
+fn labels_to_vec(labels: &Mat) -> Result<Vec<i32>> {
+    let mut converted = Mat::default();
+    labels.convert_to(&mut converted, CV_32S, 1., 0.)?;
+    return match labels.channels() {
+        1 => Ok(converted.data_typed::<i32>()?.to_vec()),
+        3 => Ok(converted
+            .data_typed::<Vec3i>()?
+            .iter()
+            .map(|c| c[0] | (c[1] << 8) | (c[2] << 16))
+            .collect()),
+        channels => Err(anyhow!("label images with {channels} channels are not supported")),
+    };
+}
@@ -0,0 +1,11 @@
+#[cfg(test)]
+mod test;
+mod contingency_table;
+mod boundaries;
+mod metrics;
+use contingency_table::*;
+use boundaries::*;
+pub(crate) use boundaries::boundary_mask;
+pub use metrics::*;
+
+use crate::prelude::*;
@@ -0,0 +1,70 @@
+use crate::prelude::*;
+use opencv::core::{Scalar, CV_32S, CV_8U};
+
+/// image split in two vertical halves, with the cut at column `cut`
+fn two_halves(width: i32, height: i32, cut: i32, typ: i32) -> Mat {
+    let mut labels = Mat::new_rows_cols_with_default(height, width, typ, Scalar::all(0.))
+        .expect("mat creation should not fail");
+    if cut < width {
+        let mut right = Mat::roi_mut(&mut labels, opencv::core::Rect::new(cut, 0, width - cut, height))
+            .expect("roi should not fail");
+        right.set_to(&Scalar::all(1.), &opencv::core::no_array()).expect("set to should not fail");
+    }
+    return labels;
+}
+
+#[test]
+fn test_identical_segmentations() {
+    let segmentation = two_halves(20, 10, 10, CV_32S);
+    // the ground truth can use any type and any label value
+    let ground_truth = two_halves(20, 10, 10, CV_8U);
+
+    let metrics = EvaluationMetrics::compute(&segmentation, &[ground_truth], 0)
+        .expect("evaluation should not fail");
+    assert_eq!(metrics.boundary_recall, 1.);
+    assert_eq!(metrics.boundary_precision, 1.);
+    assert_eq!(metrics.undersegmentation_error, 0.);
+    assert_eq!(metrics.achievable_segmentation_accuracy, 1.);
+    assert!(metrics.variation_of_information.abs() < 1e-9);
+    assert_eq!(metrics.probabilistic_rand_index, 1.);
+}
+
+#[test]
+fn test_shifted_boundary() {
+    let segmentation = two_halves(20, 10, 12, CV_32S);
+    let ground_truth = two_halves(20, 10, 10, CV_32S);
+
+    let metrics = EvaluationMetrics::compute(&segmentation, &[ground_truth.clone()], 1)
+        .expect("evaluation should not fail");
+    assert_eq!(metrics.boundary_recall, 0.);
+    assert_eq!(metrics.boundary_precision, 0.);
+
+    let metrics = EvaluationMetrics::compute(&segmentation, &[ground_truth], 2)
+        .expect("evaluation should not fail");
+    assert_eq!(metrics.boundary_recall, 1.);
+    assert_eq!(metrics.boundary_precision, 1.);
+
+    // 2 columns of 10 pixels are in the wrong region, they are counted once for each of the two
+    // ground truth regions touched by the leaking region
+    assert!((metrics.achievable_segmentation_accuracy - 180. / 200.).abs() < 1e-9);
+    assert!((metrics.undersegmentation_error - 40. / 200.).abs() < 1e-9);
+    assert!(metrics.variation_of_information > 0.);
+    assert!(metrics.probabilistic_rand_index < 1.);
+}
+
+#[test]
+fn test_single_region_segmentation() {
+    let segmentation = two_halves(20, 10, 20, CV_32S);
+    let ground_truth = two_halves(20, 10, 10, CV_32S);
+
+    let metrics = EvaluationMetrics::compute(&segmentation, &[ground_truth], 0)
+        .expect("evaluation should not fail");
+    assert_eq!(metrics.boundary_recall, 0.);
+    // no boundaries in the segmentation
+    assert_eq!(metrics.boundary_precision, 1.);
+    assert!((metrics.achievable_segmentation_accuracy - 0.5).abs() < 1e-9);
+    assert!((metrics.undersegmentation_error - 1.).abs() < 1e-9);
+    assert!((metrics.variation_of_information - 2f64.ln()).abs() < 1e-9);
+    // half of the couples of pixels are in different regions in the ground truth
+    assert!((metrics.probabilistic_rand_index - 99. / 199.).abs() < 1e-9);
+}
@@ -1,75 +1,115 @@
-use opencv::core::{Mat, Rect, Scalar};
+use opencv::core::{Mat, Point, Rect, Scalar, Size, CV_8U};
 use opencv::imgproc::rectangle;
 
 use crate::prelude::*;
 
-#[derive(Debug, Clone)]
-pub enum AreaMarker {
-    SquaredArea {
-        height: i32,
-        width: i32,
-        y_start: i32,
-        x_start: i32,
-    },
-    MaskedArea(Mat),
+/// compact representation of an area of the image: a list of non overlapping rectangles
+/// (the leafs of the split tree the area is made of).
+/// A mask is only rasterized on demand, and can be limited to a portion of the image
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct AreaMarker {
+    rects: Vec<Rect>,
 }
 
 impl AreaMarker {
-    fn get_mat_area(&mut self, reference_mat: &Mat) -> &Mat {
-        self.to_maks(reference_mat);
-        match &(*self) {
-            AreaMarker::MaskedArea(v) => &v,
-            _ => panic!("error in code... to_mask hasn't workd"),
-        }
+    pub fn new_from_rect(rect: Rect) -> Self {
+        return Self { rects: vec![rect] };
     }
-    fn to_maks(&mut self, reference_mat: &Mat) {
-        let (height, width, y, x) = match self {
-            Self::SquaredArea {
-                height,
-                width,
-                y_start,
-                x_start,
-            } => (*height, *width, *y_start, *x_start),
-            _ => return,
-        };
 
-        let new_mask = Mat::zeros(
-            reference_mat.rows(),
-            reference_mat.cols(),
-            opencv::core::CV_8U,
-        )
-        .expect("error in creating mat");
-        let mut new_mask = new_mask.to_mat().expect("error in creating mat");
-
-        rectangle(
-            &mut new_mask,
-            Rect::new(x, y, width, height),
-            Scalar::new(255., 255., 255., 0.),
-            -1,
-            opencv::imgproc::LINE_8,
-            0,
-        )
-        .expect("generation of rectangle has failed");
-
-        *self = Self::MaskedArea(new_mask)
+    pub fn get_rects(&self) -> &[Rect] {
+        return &self.rects;
+    }
+
+    pub fn pixel_count(&self) -> usize {
+        return self
+            .rects
+            .iter()
+            .map(|r| (r.width * r.height) as usize)
+            .sum();
+    }
+
+    pub fn bounding_box(&self) -> Rect {
+        return self
+            .rects
+            .iter()
+            .copied()
+            .reduce(bounding_box_union)
+            .unwrap_or_default();
+    }
+
+    /// iterate over the coordinates of all the pixels of the area
+    pub fn pixels(&self) -> impl Iterator<Item = Point> + '_ {
+        return self.rects.iter().flat_map(|r| {
+            (r.y..r.y + r.height)
+                .flat_map(move |y| (r.x..r.x + r.width).map(move |x| Point::new(x, y)))
+        });
+    }
+
+    /// rasterize the area in a `CV_8U` mask of the given size
+    pub fn to_mask(&self, size: Size) -> Result<Mat> {
+        return self.to_mask_in(Rect::new(0, 0, size.width, size.height));
+    }
+
+    /// rasterize only the part of the area that falls inside `frame`, the returned mask has the
+    /// size of the frame, and its origin is the top left corner of the frame
+    pub fn to_mask_in(&self, frame: Rect) -> Result<Mat> {
+        let mut mask = Mat::zeros(frame.height, frame.width, CV_8U)?.to_mat()?;
+
+        for rect in &self.rects {
+            let rect = match rect_intersection(*rect, frame) {
+                Some(r) => r,
+                None => continue,
+            };
+            rectangle(
+                &mut mask,
+                Rect::new(rect.x - frame.x, rect.y - frame.y, rect.width, rect.height),
+                Scalar::new(255., 255., 255., 0.),
+                -1,
+                opencv::imgproc::LINE_8,
+                0,
+            )?;
+        }
+        return Ok(mask);
     }
 
-    pub fn merge(area_1: &Mat, area_2: &Mat) -> Result<Self>{
-        let mut result = Mat::default();
-        opencv::core::bitwise_or(&area_1, &area_2, &mut result, &Mat::default())?;
-        return Ok(Self::MaskedArea(result))
+    /// merge two (non overlapping) areas. The rectangles of the smaller area are moved into the
+    /// bigger one, so that repeated merges stay cheap
+    pub fn merge(area_1: Self, area_2: Self) -> Self {
+        let (mut big, small) = if area_1.rects.len() >= area_2.rects.len() {
+            (area_1, area_2)
+        } else {
+            (area_2, area_1)
+        };
+        big.rects.extend(small.rects);
+        return big;
     }
 }
 
 impl From<&ImageContainerSplit<'_>> for AreaMarker {
     fn from(value: &ImageContainerSplit<'_>) -> Self {
-        Self::SquaredArea {
-            height: value.height,
-            width: value.width,
-            y_start: value.y_start,
-            x_start: value.x_start,
-        }
+        return Self::new_from_rect(value.get_rect());
+    }
+}
+
+/// smallest rectangle that contains both `a` and `b`
+pub fn bounding_box_union(a: Rect, b: Rect) -> Rect {
+    let x = a.x.min(b.x);
+    let y = a.y.min(b.y);
+    let x_end = (a.x + a.width).max(b.x + b.width);
+    let y_end = (a.y + a.height).max(b.y + b.height);
+    return Rect::new(x, y, x_end - x, y_end - y);
+}
+
+/// intersection of `a` and `b`, `None` if they don't overlap
+pub fn rect_intersection(a: Rect, b: Rect) -> Option<Rect> {
+    let x = a.x.max(b.x);
+    let y = a.y.max(b.y);
+    let x_end = (a.x + a.width).min(b.x + b.width);
+    let y_end = (a.y + a.height).min(b.y + b.height);
+    if x_end <= x || y_end <= y {
+        return None;
     }
+    return Some(Rect::new(x, y, x_end - x, y_end - y));
 }
 
 #[derive(Debug, Clone)]
@@ -82,12 +122,7 @@ impl Area {
     pub fn new(id: usize, height: i32, width: i32) -> Self {
         return Self {
             id,
-            marker: AreaMarker::SquaredArea {
-                height,
-                width,
-                y_start: 0,
-                x_start: 0,
-            },
+            marker: AreaMarker::new_from_rect(Rect::new(0, 0, width, height)),
         };
     }
     
@@ -102,7 +137,13 @@ impl Area {
         };
     }
 
-    pub fn get_mat_area(&mut self, reference_mat: &Mat) -> &Mat {
-        self.marker.get_mat_area(reference_mat)
+    /// rasterize the area in a mask with the same size of `reference_mat`
+    pub fn to_mask(&self, reference_mat: &Mat) -> Result<Mat> {
+        self.marker.to_mask(reference_mat.size()?)
+    }
+
+    /// rasterize the area in a mask that covers only `frame` (see `AreaMarker::to_mask_in`)
+    pub fn to_mask_in(&self, frame: Rect) -> Result<Mat> {
+        self.marker.to_mask_in(frame)
     }
 }
@@ -0,0 +1,39 @@
+use opencv::imgproc::{
+    cvt_color, COLOR_BGR2GRAY, COLOR_BGR2HSV, COLOR_BGR2Lab, COLOR_BGR2YCrCb,
+};
+
+use crate::prelude::*;
+
+/// number of distinct hues in the 8 bit HSV encoding of OpenCV, 0 and `HUE_RANGE` are the same
+/// hue
+pub const HUE_RANGE: f64 = 180.;
+
+/// color space in which splitters and mergers evaluate the image
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ColorSpace {
+    /// the color space of the images loaded by OpenCV
+    #[default]
+    Bgr,
+    /// CIELAB (8 bit OpenCV encoding: L is scaled to 0..255 and a, b are shifted by 128)
+    Lab,
+    /// HSV (8 bit OpenCV encoding: the hue goes from 0 to `HUE_RANGE`, and wraps around)
+    Hsv,
+    YCrCb,
+    Gray,
+}
+
+impl ColorSpace {
+    /// convert a BGR image to this color space, `None` if no conversion is needed
+    pub fn convert_from_bgr(&self, image: &Mat) -> Result<Option<Mat>> {
+        let code = match self {
+            ColorSpace::Bgr => return Ok(None),
+            ColorSpace::Lab => COLOR_BGR2Lab,
+            ColorSpace::Hsv => COLOR_BGR2HSV,
+            ColorSpace::YCrCb => COLOR_BGR2YCrCb,
+            ColorSpace::Gray => COLOR_BGR2GRAY,
+        };
+        let mut converted = Mat::default();
+        cvt_color(image, &mut converted, code, 0)?;
+        return Ok(Some(converted));
+    }
+}
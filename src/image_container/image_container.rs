@@ -1,36 +1,138 @@
 use crate::prelude::*;
+use opencv::core::{extract_channel, Rect};
 use opencv::imgcodecs::{imread, ImreadModes};
+use opencv::imgproc::{cvt_color, COLOR_BGR2HSV};
+use std::path::Path;
+use std::sync::OnceLock;
+
+/// extensions (in lowercase) of the files that are read as images when a whole directory is
+/// processed
+pub const IMAGE_EXTENSIONS: [&str; 11] = [
+    "bmp", "jpg", "jpeg", "png", "tif", "tiff", "webp", "pbm", "pgm", "ppm", "pnm",
+];
 
 /// Structure that contains the image that is been analyzed and split;
 #[derive(Debug)]
 pub struct ImageContainer {
+    /// the image in the selected color space, it is the one evaluated by splitters and mergers
     pub image: Mat,
+    pub color_space: ColorSpace,
+    /// the image as it has been loaded, `None` if it is the same as `image`
+    original_image: Option<Mat>,
     pub height: i32,
     pub width: i32,
+    /// integral images of `image`, used to get the statistics of any split in O(1)
+    pub integral_image: IntegralImage,
+    /// the images derived from `image` are computed only the first time they are needed
+    hue_integral_image: OnceLock<IntegralImage>,
+    lbp_image: OnceLock<Mat>,
 }
 
+
 impl ImageContainer {
+    /// check from the extension if a file is one of the images that can be loaded
+    pub fn is_image_file(path: &Path) -> bool {
+        return path
+            .extension()
+            .map(|x| x.to_string_lossy().to_lowercase())
+            .is_some_and(|x| IMAGE_EXTENSIONS.contains(&x.as_str()));
+    }
+
     pub fn new_from_file(path: &str, mode: ImreadModes) -> Result<Self> {
         let mat = imread(path, mode.into())?;
+        if mat.empty() {
+            return Err(anyhow!("unable to read the image at {path}"));
+        }
+        Self::new_from_mat(mat)
+    }
+
+    pub fn new_from_mat(mat: Mat) -> Result<Self> {
         let size = mat.size()?;
         Ok(ImageContainer {
+            integral_image: IntegralImage::new(&mat)?,
             image: mat,
+            color_space: ColorSpace::Bgr,
+            original_image: None,
             height: size.height,
             width: size.width,
+            hue_integral_image: OnceLock::new(),
+            lbp_image: OnceLock::new(),
         })
     }
 
+    /// convert the image (that must be a BGR image) to another color space.
+    /// The conversion is done only once, the integral images are computed from the converted
+    /// image
+    pub fn with_color_space(self, color_space: ColorSpace) -> Result<Self> {
+        let original_image = self.original_image.unwrap_or(self.image);
+        let (image, original_image) = match color_space.convert_from_bgr(&original_image)? {
+            Some(converted) => (converted, Some(original_image)),
+            None => (original_image, None),
+        };
+
+        let mut container = Self::new_from_mat(image)?;
+        container.color_space = color_space;
+        container.original_image = original_image;
+        return Ok(container);
+    }
+
+    /// the image as it has been loaded (before the color space conversion), it is the one that
+    /// should be used to display the results
+    pub fn get_original_image(&self) -> &Mat {
+        return self.original_image.as_ref().unwrap_or(&self.image);
+    }
+
+    /// integral images of the hue channel of the image. With `ColorSpace::Hsv` it is taken from
+    /// `image`, the other color spaces have no hue channel so it is computed from the original
+    /// (BGR) image
+    pub fn get_hue_integral_image(&self) -> Result<&IntegralImage> {
+        if let Some(integral_image) = self.hue_integral_image.get() {
+            return Ok(integral_image);
+        }
+        let mut hue = Mat::default();
+        if self.color_space == ColorSpace::Hsv {
+            extract_channel(&self.image, &mut hue, 0)?;
+        } else {
+            let mut hsv = Mat::default();
+            cvt_color(self.get_original_image(), &mut hsv, COLOR_BGR2HSV, 0)?;
+            extract_channel(&hsv, &mut hue, 0)?;
+        }
+
+        // if another thread got here first, its (identical) result is kept
+        let _ = self.hue_integral_image.set(IntegralImage::new(&hue)?);
+        return Ok(self.hue_integral_image.get().expect("the value has just been set"));
+    }
+
     pub fn new_from_file_color(path: &str) -> Result<Self> {
         Self::new_from_file(path, ImreadModes::IMREAD_COLOR)
     }
 
     pub fn to_image_container_split<'a>(&'a self) -> ImageContainerSplit<'a> {
-        return ImageContainerSplit {
-            image: self.image.row_bounds(0, self.height).expect("height should always be small enough"),
-            x_start: 0,
-            y_start: 0,
-            height: self.height,
-            width: self.width,
-        };
+        return self
+            .get_split(Rect::new(0, 0, self.width, self.height))
+            .expect("the whole image should always be a valid area");
+    }
+
+    /// local binary pattern of every pixel of the original image (see `compute_lbp_image`)
+    pub fn get_lbp_image(&self) -> Result<&Mat> {
+        if let Some(lbp_image) = self.lbp_image.get() {
+            return Ok(lbp_image);
+        }
+        // if another thread got here first, its (identical) result is kept
+        let _ = self.lbp_image.set(compute_lbp_image(self.get_original_image())?);
+        return Ok(self.lbp_image.get().expect("the value has just been set"));
+    }
+
+    /// return a view on a rectangular area of the image, the view borrows only the container
+    /// so it can be kept independently from the split it has been generated from
+    pub fn get_split<'a>(&'a self, area: Rect) -> Result<ImageContainerSplit<'a>> {
+        return Ok(ImageContainerSplit {
+            image: Mat::roi(&self.image, area)?,
+            container: self,
+            x_start: area.x,
+            y_start: area.y,
+            height: area.height,
+            width: area.width,
+        });
     }
 }
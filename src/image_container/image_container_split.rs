@@ -1,12 +1,14 @@
 use crate::prelude::*;
 use anyhow::{anyhow, Result};
 use opencv::boxed_ref::BoxedRef;
+use opencv::core::Rect;
 
 
 /// Structure that contains a rectangular split that starts from an ImageContainer
 #[derive(Debug)]
 pub struct ImageContainerSplit<'a> {
     pub image: BoxedRef<'a, Mat>,
+    pub container: &'a ImageContainer,
     pub x_start: i32,
     pub y_start: i32,
     pub height: i32,
@@ -15,53 +17,83 @@ pub struct ImageContainerSplit<'a> {
 
 
 
-impl ImageContainerSplit<'_> {
+impl<'a> ImageContainerSplit<'a> {
 
     pub fn split(
         &self,
         direction: CutDirection,
         split_at: i32,
-    ) -> Result<[ImageContainerSplit<'_>; 2]> {
+    ) -> Result<[ImageContainerSplit<'a>; 2]> {
 
         if split_at <= 0{
             return Err(anyhow!("split_at={} shall not be zero or negative",split_at))
         }
 
+        let (x, y, h, w) = (self.x_start, self.y_start, self.height, self.width);
+
         let [a, b] = match (direction, split_at>=self.height, split_at>=self.width) {
             (CutDirection::CutParallelToX,true,_) => return Err(anyhow!("split_at={} is out of bound for Y axis of height={}",split_at,self.height)),
             (CutDirection::CutParallelToY,_,true) => return Err(anyhow!("split_at={} is out of bound for X axis of width={}",split_at,self.width)),
             (CutDirection::CutParallelToX,_,_) => [
-                self.image.row_bounds(0, split_at)?,
-                self.image.row_bounds(split_at, self.height)?,
+                Rect::new(x, y, w, split_at),
+                Rect::new(x, y + split_at, w, h - split_at),
             ],
             (CutDirection::CutParallelToY, _, _) => [
-                self.image.col_bounds(0, split_at)?,
-                self.image.col_bounds(split_at, self.width)?,
+                Rect::new(x, y, split_at, h),
+                Rect::new(x + split_at, y, w - split_at, h),
             ],
         };
 
-        let y_split = direction == CutDirection::CutParallelToY;
-        let x_split = !y_split;
-
+        // the new splits reference directly the container (and not self), so that they
+        // can outlive this split
         return Ok([
-            ImageContainerSplit {
-                image: a,
-                x_start: self.x_start,
-                y_start: self.y_start,
-                height: if y_split { self.height } else { split_at },
-                width: if x_split { self.width } else { split_at },
-            },
-            ImageContainerSplit {
-                image: b,
-                x_start: self.x_start + if x_split { 0 } else { split_at },
-                y_start: self.y_start + if y_split { 0 } else { split_at },
-                height: if y_split { self.height } else { self.height - split_at },
-                width: if x_split { self.width } else { self.width - split_at },
-            },
+            self.container.get_split(a)?,
+            self.container.get_split(b)?,
         ]);
 
     }
 
+    /// split the area as requested by a splitter
+    pub fn apply_split(&self, split: Split) -> Result<Vec<ImageContainerSplit<'a>>> {
+        return match split {
+            Split::Binary(direction, split_at) => Ok(self.split(direction, split_at)?.into()),
+            Split::Quad { x, y } => {
+                let [left, right] = self.split(CutDirection::CutParallelToY, x)?;
+                let [top_left, bottom_left] = left.split(CutDirection::CutParallelToX, y)?;
+                let [top_right, bottom_right] = right.split(CutDirection::CutParallelToX, y)?;
+                Ok(vec![top_left, bottom_left, top_right, bottom_right])
+            }
+        };
+    }
+
+    /// statistics of the pixels of the split, computed in O(1) from the integral images of the
+    /// container
+    pub fn get_statistics(&self) -> Result<RegionStatistics> {
+        return self.container.integral_image.get_statistics(self.get_rect());
+    }
+
+    /// statistics of the hue channel of the split (see `ImageContainer::get_hue_integral_image`)
+    pub fn get_hue_statistics(&self) -> Result<RegionStatistics> {
+        return self
+            .container
+            .get_hue_integral_image()?
+            .get_statistics(self.get_rect());
+    }
+
+    /// histogram of the local binary patterns of the split
+    pub fn get_texture_histogram(&self) -> Result<TextureHistogram> {
+        return TextureHistogram::new_from_lbp_image(self.container.get_lbp_image()?, self.get_rect());
+    }
+
+    /// create a new view on the same area of the image
+    pub fn try_clone(&self) -> Result<ImageContainerSplit<'a>> {
+        return self.container.get_split(self.get_rect());
+    }
+
+    pub fn get_rect(&self) -> Rect {
+        return Rect::new(self.x_start, self.y_start, self.width, self.height);
+    }
+
     pub fn are_neighbors(a: &Self, b: &Self) -> bool{
         
         fn overlap(a_start: i32, a_len: i32, b_start: i32, b_len: i32) -> bool{
@@ -0,0 +1,77 @@
+use opencv::core::{Rect, ToInputArray, CV_64F};
+use opencv::imgproc::integral2;
+
+use crate::prelude::*;
+
+/// integral images of the sum and of the squared sum of each channel of an image, that allow to
+/// compute the statistics of any rectangle of the image in O(1)
+#[derive(Debug)]
+pub struct IntegralImage {
+    sum: Mat,
+    sq_sum: Mat,
+    channels: usize,
+    width: i32,
+    height: i32,
+}
+
+impl IntegralImage {
+    pub fn new(image: &impl ToInputArray) -> Result<Self> {
+        let mut sum = Mat::default();
+        let mut sq_sum = Mat::default();
+        integral2(image, &mut sum, &mut sq_sum, CV_64F, CV_64F)?;
+
+        // the integral images have one row and one column more than the image
+        let channels = sum.channels() as usize;
+        if channels > 4 {
+            return Err(anyhow!("images with {channels} channels are not supported"));
+        }
+        // stored with a single channel, so that the values can be read as a plain slice of f64
+        return Ok(Self {
+            channels,
+            width: sum.cols() - 1,
+            height: sum.rows() - 1,
+            sum: sum.reshape(1, 0)?.try_clone()?,
+            sq_sum: sq_sum.reshape(1, 0)?.try_clone()?,
+        });
+    }
+
+    /// statistics of the pixels inside `rect` (in the coordinates of the image the integral
+    /// images have been computed from)
+    pub fn get_statistics(&self, rect: Rect) -> Result<RegionStatistics> {
+        if rect.x < 0
+            || rect.y < 0
+            || rect.width < 0
+            || rect.height < 0
+            || rect.x + rect.width > self.width
+            || rect.y + rect.height > self.height
+        {
+            return Err(anyhow!(
+                "{rect:?} is out of bound for an image of size {}x{}",
+                self.width,
+                self.height
+            ));
+        }
+
+        let sum = self.sum.data_typed::<f64>()?;
+        let sq_sum = self.sq_sum.data_typed::<f64>()?;
+
+        let stride = (self.width + 1) as usize * self.channels;
+        let index = |x: i32, y: i32, c: usize| y as usize * stride + x as usize * self.channels + c;
+        let rect_value = |data: &[f64], c: usize| {
+            let (x0, y0) = (rect.x, rect.y);
+            let (x1, y1) = (rect.x + rect.width, rect.y + rect.height);
+            data[index(x1, y1, c)] - data[index(x0, y1, c)] - data[index(x1, y0, c)]
+                + data[index(x0, y0, c)]
+        };
+
+        let mut statistics = RegionStatistics {
+            pixel_count: (rect.width * rect.height) as usize,
+            ..Default::default()
+        };
+        for c in 0..self.channels {
+            statistics.sum[c] = rect_value(sum, c);
+            statistics.sum_sq[c] = rect_value(sq_sum, c);
+        }
+        return Ok(statistics);
+    }
+}
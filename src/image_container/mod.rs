@@ -3,10 +3,18 @@ mod test;
 mod image_container;
 mod image_container_split;
 mod cut_direction;
-mod unmanaged_mat;
+mod split;
 mod area;
+mod region_statistics;
+mod integral_image;
+mod texture;
+mod color_space;
 pub use image_container_split::*;
 pub use image_container::*;
 pub use cut_direction::*;
-pub use unmanaged_mat::*;
+pub use split::*;
 pub use area::*;
+pub use region_statistics::*;
+pub use integral_image::*;
+pub use texture::*;
+pub use color_space::*;
@@ -0,0 +1,86 @@
+use opencv::core::{mean_std_dev, no_array, Scalar, VecN};
+
+use crate::prelude::*;
+
+/// running statistics of the pixels of a region. Since regions are only ever built as the union
+/// of two disjoint regions, the statistics of a merged region can be combined in O(1)
+/// from the statistics of its parts, without scanning the pixels again
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct RegionStatistics {
+    pub pixel_count: usize,
+    /// per channel sum of the pixel values
+    pub sum: [f64; 4],
+    /// per channel sum of the squared pixel values
+    pub sum_sq: [f64; 4],
+    /// histogram of the local binary patterns, only computed for the mergers that need it
+    /// (see `MergerTrait::requires_texture`)
+    pub texture: Option<TextureHistogram>,
+}
+
+impl RegionStatistics {
+    /// compute the statistics of a rectangular split by scanning its pixels
+    /// (`ImageContainerSplit::get_statistics` obtains the same result in O(1))
+    pub fn new_from_split(split: &ImageContainerSplit<'_>) -> Result<Self> {
+        let mut mean = Scalar::default();
+        let mut std_dev = Scalar::default();
+        mean_std_dev(&split.image, &mut mean, &mut std_dev, &no_array())?;
+
+        let pixel_count = (split.width * split.height) as usize;
+        let n = pixel_count as f64;
+        return Ok(Self {
+            pixel_count,
+            sum: mean.0.map(|m| m * n),
+            sum_sq: [0, 1, 2, 3].map(|c| n * (std_dev[c].powi(2) + mean[c].powi(2))),
+            texture: None,
+        });
+    }
+
+    /// statistics of the union of two disjoint regions
+    pub fn merge(a: &Self, b: &Self) -> Self {
+        return Self {
+            pixel_count: a.pixel_count + b.pixel_count,
+            sum: [0, 1, 2, 3].map(|c| a.sum[c] + b.sum[c]),
+            sum_sq: [0, 1, 2, 3].map(|c| a.sum_sq[c] + b.sum_sq[c]),
+            texture: match (&a.texture, &b.texture) {
+                (Some(a), Some(b)) => Some(TextureHistogram::merge(a, b)),
+                _ => None,
+            },
+        };
+    }
+
+    pub fn mean(&self) -> Scalar {
+        if self.pixel_count == 0 {
+            return Scalar::default();
+        }
+        let n = self.pixel_count as f64;
+        return VecN(self.sum.map(|s| s / n));
+    }
+
+    /// per channel (population) variance, with the same definition used by `mean_std_dev`
+    pub fn variance(&self) -> Scalar {
+        if self.pixel_count == 0 {
+            return Scalar::default();
+        }
+        let n = self.pixel_count as f64;
+        let mean = self.mean();
+        // clamped at zero since rounding errors could make it slightly negative
+        return VecN([0, 1, 2, 3].map(|c| (self.sum_sq[c] / n - mean[c].powi(2)).max(0.)));
+    }
+
+    /// sum over all the channels of the squared distances of the pixels from the mean
+    /// (the variance multiplied by the number of pixels)
+    pub fn sum_of_squared_errors(&self) -> f64 {
+        if self.pixel_count == 0 {
+            return 0.;
+        }
+        let n = self.pixel_count as f64;
+        return [0, 1, 2, 3]
+            .map(|c| (self.sum_sq[c] - self.sum[c].powi(2) / n).max(0.))
+            .iter()
+            .sum();
+    }
+
+    pub fn std_dev(&self) -> Scalar {
+        return VecN(self.variance().0.map(f64::sqrt));
+    }
+}
@@ -0,0 +1,11 @@
+use super::*;
+
+/// how an area must be split, as returned by a splitter
+#[derive(Eq, PartialEq, Debug, Clone, Copy)]
+pub enum Split {
+    /// split in two areas with a single cut at `split_at` (relative to the area)
+    Binary(CutDirection, i32),
+    /// split in four quadrants, with a cut parallel to Y at `x` and a cut parallel to X at `y`
+    /// (both relative to the area)
+    Quad { x: i32, y: i32 },
+}
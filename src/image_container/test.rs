@@ -81,17 +81,190 @@ fn test_split_parallel_to_x() {
 }
 
 #[test]
-fn test_unmanaged_mat_correct_disposal(){
+fn test_split_outlives_parent(){
     let i = ImageContainer::new_from_file_color("./test_images/200x100_split.jpg").expect("test file must be present");
+    let parent = i.to_image_container_split();
+
+    let [c1,c2] = parent.split(CutDirection::CutParallelToY, 150).expect("this split should not fail");
+    drop(parent);
+
+    verify_consistency(&c1);
+    verify_consistency(&c2);
+
+    let c3 = c2.try_clone().expect("clone should not fail");
+    drop(c2);
+    verify_consistency(&c3);
+    assert_eq!(c3.x_start,150);
+    assert_eq!(c3.width,50);
+}
+
+#[test]
+fn test_area_marker_merge_and_rasterize(){
+    let i = ImageContainer::new_from_file_color("./test_images/200x100_split.jpg").expect("test file must be present");
+    let i = i.to_image_container_split();
+
+    let [c1,c2] = i.split(CutDirection::CutParallelToY, 150).expect("this split should not fail");
+    let [c3,c4] = c2.split(CutDirection::CutParallelToX, 25).expect("this split should not fail");
+
+    let merged = AreaMarker::merge(AreaMarker::from(&c1), AreaMarker::from(&c3));
+    assert_eq!(merged.get_rects().len(), 2);
+    assert_eq!(merged.pixel_count(), 150 * 100 + 50 * 25);
+    assert_eq!(merged.pixels().count(), merged.pixel_count());
+    assert_eq!(merged.bounding_box(), opencv::core::Rect::new(0, 0, 200, 100));
+
+    let mask = merged.to_mask(i.image.size().expect("should't fail")).expect("rasterization should not fail");
+    assert_eq!(opencv::core::count_non_zero(&mask).expect("should't fail") as usize, merged.pixel_count());
+
+    // only the part of the area inside the frame is rasterized
+    let frame = c4.get_rect();
+    let mask = merged.to_mask_in(frame).expect("rasterization should not fail");
+    assert_eq!(mask.size().expect("should't fail"), opencv::core::Size::new(frame.width, frame.height));
+    assert_eq!(opencv::core::count_non_zero(&mask).expect("should't fail"), 0);
+}
+
+#[test]
+fn test_region_statistics_merge(){
+    let i = ImageContainer::new_from_file_color("./test_images/200x100_split.jpg").expect("test file must be present");
+    let i = i.to_image_container_split();
+
+    let [c1,c2] = i.split(CutDirection::CutParallelToY, 150).expect("this split should not fail");
+
+    let whole = RegionStatistics::new_from_split(&i).expect("statistics should not fail");
+    let merged = RegionStatistics::merge(
+        &RegionStatistics::new_from_split(&c1).expect("statistics should not fail"),
+        &RegionStatistics::new_from_split(&c2).expect("statistics should not fail"),
+    );
+
+    assert_eq!(merged.pixel_count, whole.pixel_count);
+    for c in 0..4 {
+        assert!((merged.mean()[c] - whole.mean()[c]).abs() < 1e-6);
+        assert!((merged.std_dev()[c] - whole.std_dev()[c]).abs() < 1e-6);
+    }
+}
+
+#[test]
+fn test_integral_image_statistics(){
+    let i = ImageContainer::new_from_file_color("./test_images/200x100_split.jpg").expect("test file must be present");
+    let integral_image = IntegralImage::new(&i.image).expect("integral image should not fail");
     let i = i.to_image_container_split();
-    let unmanaged_mat = unsafe {UnmanagedMat::from_image_container_split(&i)};
-    unmanaged_mat.destroy();
-}  
+
+    let [c1,c2] = i.split(CutDirection::CutParallelToX, 60).expect("this split should not fail");
+    let [_,c3] = c2.split(CutDirection::CutParallelToY, 25).expect("this split should not fail");
+
+    for split in [&i, &c1, &c3] {
+        let expected = RegionStatistics::new_from_split(split).expect("statistics should not fail");
+        let from_integral = integral_image.get_statistics(split.get_rect()).expect("statistics should not fail");
+        let from_container = split.get_statistics().expect("statistics should not fail");
+        for actual in [from_integral, from_container] {
+            assert_eq!(actual.pixel_count, expected.pixel_count);
+            for c in 0..4 {
+                assert!((actual.mean()[c] - expected.mean()[c]).abs() < 1e-6);
+                assert!((actual.std_dev()[c] - expected.std_dev()[c]).abs() < 1e-6);
+            }
+        }
+    }
+
+    let _ = integral_image.get_statistics(opencv::core::Rect::new(150, 0, 51, 10)).expect_err("the rect is out of bound");
+}
 
 #[test]
-#[should_panic]
-fn test_unmanaged_mat_panic_on_drop(){
+fn test_quad_split(){
     let i = ImageContainer::new_from_file_color("./test_images/200x100_split.jpg").expect("test file must be present");
     let i = i.to_image_container_split();
-    let _ = unsafe {UnmanagedMat::from_image_container_split(&i)};
+
+    let _ = i.apply_split(Split::Quad { x: 200, y: 50 }).expect_err("this split should fail");
+
+    let splits = i.apply_split(Split::Quad { x: 150, y: 60 }).expect("this split should not fail");
+    assert_eq!(splits.len(), 4);
+    for split in &splits {
+        verify_consistency(split);
+    }
+
+    let rects = splits.iter().map(|s| s.get_rect()).collect::<Vec<_>>();
+    assert_eq!(rects, vec![
+        opencv::core::Rect::new(0, 0, 150, 60),
+        opencv::core::Rect::new(0, 60, 150, 40),
+        opencv::core::Rect::new(150, 0, 50, 60),
+        opencv::core::Rect::new(150, 60, 50, 40),
+    ]);
+}
+
+#[test]
+fn test_texture_histogram(){
+    use opencv::core::{Rect, Scalar, CV_8UC1};
+
+    // left half flat, right half with vertical stripes one pixel wide
+    let mut image = Mat::new_rows_cols_with_default(10, 20, CV_8UC1, Scalar::all(0.)).expect("mat creation should not fail");
+    for x in (10..20).step_by(2) {
+        opencv::imgproc::rectangle(&mut image, Rect::new(x, 0, 1, 10), Scalar::all(255.), -1, opencv::imgproc::LINE_8, 0).expect("rectangle should not fail");
+    }
+    let i = ImageContainer::new_from_mat(image).expect("container creation should not fail");
+    let i = i.to_image_container_split();
+
+    let [flat, striped] = i.split(CutDirection::CutParallelToY, 10).expect("this split should not fail");
+    let flat = flat.get_texture_histogram().expect("histogram should not fail");
+    let striped = striped.get_texture_histogram().expect("histogram should not fail");
+
+    // on a flat area no neighbor is darker than the center, so every pixel has all the bits set
+    assert_eq!(flat.bins.iter().sum::<u64>(), 100);
+    assert_eq!(flat.bins[8], 100);
+
+    // on the dark stripes every neighbor is brighter or equal, so all the bits are set. On the
+    // bright stripes only the two neighbors above and below (equal to the center) set their bit,
+    // the ones on the sides are darker: two separate bits are a non uniform pattern
+    assert_eq!(striped.bins[8], 50);
+    assert_eq!(striped.bins[LBP_BINS - 1], 50);
+
+    assert_eq!(flat.chi_square_distance(&flat), 0.);
+    assert!(flat.chi_square_distance(&striped) > 0.3);
+
+    let merged = TextureHistogram::merge(&flat, &striped);
+    assert_eq!(merged.bins.iter().sum::<u64>(), 200);
+}
+
+#[test]
+fn test_color_space_conversion() {
+    let i = ImageContainer::new_from_file_color("./test_images/200x100_split.jpg")
+        .expect("test file must be present")
+        .with_color_space(ColorSpace::Gray)
+        .expect("conversion to gray should not fail");
+
+    assert_eq!(i.color_space, ColorSpace::Gray);
+    assert_eq!(i.image.channels(), 1);
+    assert_eq!(i.get_original_image().channels(), 3);
+    assert_eq!((i.width, i.height), (200, 100));
+
+    let split = i.to_image_container_split();
+    let stats = split.get_statistics().expect("statistics should not fail");
+    assert_eq!(stats.pixel_count, 200 * 100);
+
+    let i = ImageContainer::new_from_file_color("./test_images/200x100_split.jpg")
+        .expect("test file must be present")
+        .with_color_space(ColorSpace::Lab)
+        .expect("conversion to lab should not fail");
+    assert_eq!(i.image.channels(), 3);
+
+    // with the HSV color space the hue is read from the converted image, it must be the same
+    // hue that is computed from the original image in the other color spaces
+    let hsv = ImageContainer::new_from_file_color("./test_images/200x100_split.jpg")
+        .expect("test file must be present")
+        .with_color_space(ColorSpace::Hsv)
+        .expect("conversion to hsv should not fail");
+    let hue_stats = |i: &ImageContainer| {
+        i.to_image_container_split()
+            .get_hue_statistics()
+            .expect("hue statistics should not fail")
+    };
+    assert_eq!(hue_stats(&hsv), hue_stats(&i));
+}
+
+#[test]
+fn test_is_image_file() {
+    use std::path::Path;
+
+    assert!(ImageContainer::is_image_file(Path::new("./test_images/200x100_split.jpg")));
+    assert!(ImageContainer::is_image_file(Path::new("ground_truth/100007.PNG")));
+    assert!(!ImageContainer::is_image_file(Path::new("ground_truth/100007.seg")));
+    assert!(!ImageContainer::is_image_file(Path::new("ground_truth/.DS_Store")));
+    assert!(!ImageContainer::is_image_file(Path::new("README")));
 }
@@ -0,0 +1,108 @@
+use opencv::core::{Rect, CV_8U};
+use opencv::imgproc::{cvt_color, COLOR_BGR2GRAY};
+
+use crate::prelude::*;
+
+/// number of distinct rotation invariant uniform local binary patterns with 8 neighbors
+/// (9 uniform patterns, plus one bin for all the non uniform ones)
+pub const LBP_BINS: usize = 10;
+
+/// histogram of the local binary patterns of an area, used as a texture descriptor.
+/// Like `RegionStatistics`, the histogram of the union of two areas is the sum of the two
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct TextureHistogram {
+    pub bins: [u64; LBP_BINS],
+}
+
+impl TextureHistogram {
+    /// histogram of the patterns inside `rect`, from an image of patterns (see `compute_lbp_image`)
+    pub fn new_from_lbp_image(lbp_image: &Mat, rect: Rect) -> Result<Self> {
+        let cols = lbp_image.cols();
+        if rect.x < 0
+            || rect.y < 0
+            || rect.x + rect.width > cols
+            || rect.y + rect.height > lbp_image.rows()
+        {
+            return Err(anyhow!("{rect:?} is out of bound for the pattern image"));
+        }
+
+        let data = lbp_image.data_typed::<u8>()?;
+        let mut histogram = Self::default();
+        for y in rect.y..rect.y + rect.height {
+            let row_start = (y * cols + rect.x) as usize;
+            for pattern in &data[row_start..row_start + rect.width as usize] {
+                histogram.bins[*pattern as usize] += 1;
+            }
+        }
+        return Ok(histogram);
+    }
+
+    pub fn merge(a: &Self, b: &Self) -> Self {
+        let mut bins = a.bins;
+        for (bin, other) in bins.iter_mut().zip(b.bins) {
+            *bin += other;
+        }
+        return Self { bins };
+    }
+
+    /// chi square distance between the normalized histograms, it goes from 0 (same texture)
+    /// to 1 (no pattern in common)
+    pub fn chi_square_distance(&self, other: &Self) -> f64 {
+        let total_a = self.bins.iter().sum::<u64>().max(1) as f64;
+        let total_b = other.bins.iter().sum::<u64>().max(1) as f64;
+
+        let mut distance = 0.;
+        for (a, b) in self.bins.iter().zip(other.bins) {
+            let (a, b) = (*a as f64 / total_a, b as f64 / total_b);
+            if a + b > 0. {
+                distance += (a - b).powi(2) / (a + b);
+            }
+        }
+        return distance / 2.;
+    }
+}
+
+/// compute the rotation invariant uniform local binary pattern (8 neighbors at distance 1)
+/// of every pixel of the image. The result is a `CV_8U` image with values in `0..LBP_BINS`
+pub fn compute_lbp_image(image: &Mat) -> Result<Mat> {
+    let gray = match image.channels() {
+        1 => image.try_clone()?,
+        3 => {
+            let mut gray = Mat::default();
+            cvt_color(image, &mut gray, COLOR_BGR2GRAY, 0)?;
+            gray
+        }
+        channels => return Err(anyhow!("images with {channels} channels are not supported")),
+    };
+    if gray.depth() != CV_8U {
+        return Err(anyhow!("only 8 bit images are supported"));
+    }
+
+    let (rows, cols) = (gray.rows(), gray.cols());
+    let data = gray.data_typed::<u8>()?;
+    // the pixels outside of the image are replaced with the closest pixel on the border
+    let pixel = |y: i32, x: i32| data[(y.clamp(0, rows - 1) * cols + x.clamp(0, cols - 1)) as usize];
+
+    // neighbors in circular order
+    const NEIGHBORS: [(i32, i32); 8] = [
+        (-1, -1), (-1, 0), (-1, 1), (0, 1), (1, 1), (1, 0), (1, -1), (0, -1),
+    ];
+
+    let mut patterns = Vec::with_capacity((rows * cols) as usize);
+    for y in 0..rows {
+        for x in 0..cols {
+            let center = pixel(y, x);
+            let bits = NEIGHBORS.map(|(dy, dx)| pixel(y + dy, x + dx) >= center);
+
+            let transitions = (0..8).filter(|i| bits[*i] != bits[(i + 1) % 8]).count();
+            let pattern = if transitions <= 2 {
+                bits.iter().filter(|b| **b).count()
+            } else {
+                LBP_BINS - 1
+            };
+            patterns.push(pattern as u8);
+        }
+    }
+
+    return Ok(Mat::new_rows_cols_with_data(rows, cols, &patterns)?.try_clone()?);
+}
@@ -0,0 +1,22 @@
+/// quote a string, escaping the characters that are not allowed inside a json string
+pub(crate) fn json_string(s: &str) -> String {
+    let mut out = String::from("\"");
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    return out;
+}
+
+/// json has no representation for infinite and nan values
+pub(crate) fn json_number(x: f64) -> String {
+    if x.is_finite() {
+        return x.to_string();
+    }
+    return "null".into();
+}
@@ -0,0 +1,18 @@
+//! Multithreaded implementation of the split and merge segmentation algorithm.
+//!
+//! Everything that is needed to use the library is exported trough the [prelude]:
+//! the [MainLogic] that drives the algorithm, the [SplitterTrait], [MergerTrait] and
+//! [LoggerTrait] (with their implementations in `splitter_traits`, `merger_traits` and
+//! `logger_traits`) and the [ImageContainer] that holds the image to segment.
+mod image_container;
+mod logger_trait;
+mod main_logic;
+mod merger_trait;
+mod splitter_trait;
+mod worker_pool;
+mod evaluation;
+mod benchmark;
+mod json;
+pub mod prelude;
+
+pub use prelude::*;
@@ -1,65 +1,53 @@
 use opencv::{
     core::{
-        Scalar, VecN, BORDER_CONSTANT, CV_32FC3, CV_8U,
+        Rect, Scalar, VecN, BORDER_CONSTANT, CV_32FC3, CV_8U,
     },
     imgproc::{cvt_color, morphology_default_border_value, COLOR_HSV2BGR_FULL},
 };
+use rand::{rngs::StdRng, Rng, SeedableRng};
 use std::collections::HashMap;
 
 use crate::prelude::*;
 
 type Color = Scalar;
 
+/// size of the kernel used to separate the border of an area from its inside
+const ERODE_KERNEL_SIZE: i32 = 4;
+
 #[derive(Debug, Clone)]
 pub struct ColoredArea {
     pub color: Color,
     pub area: Area,
 }
 
-impl From<Area> for ColoredArea {
-    fn from(value: Area) -> Self {
-        Self {
-            area: value,
-            color: Self::get_random_color(),
-        }
-    }
-}
-
 impl ColoredArea {
     pub fn new(color: Color, area: Area) -> Self {
         Self { color, area }
     }
-    pub fn new_random_color(area: Area) -> Self {
-        Self {
-            color: Self::get_random_color(),
+    pub fn new_random_color(area: Area, rng: &mut impl Rng) -> Result<Self> {
+        Ok(Self {
+            color: Self::get_random_color(rng)?,
             area,
-        }
+        })
     }
-    fn get_random_color() -> Color {
-        let mut mat_input = Mat::ones(1, 1, CV_32FC3)
-            .expect("mat creation fail")
-            .to_mat()
-            .expect("mat conversion has fail");
+    /// a random fully saturated color
+    pub(crate) fn get_random_color(rng: &mut impl Rng) -> Result<Color> {
+        let mut mat_input = Mat::ones(1, 1, CV_32FC3)?.to_mat()?;
         let mut mat_output = mat_input.clone();
 
-        let p: &mut VecN<f32, 3> = mat_input.at_mut(0).expect("");
-        p[0] = rand::random::<f32>()*255.0;
+        let p: &mut VecN<f32, 3> = mat_input.at_mut(0)?;
+        p[0] = rng.gen::<f32>()*255.0;
 
         p[1] = 1.;
         p[2] = 1.;
-        cvt_color(&mat_input, &mut mat_output, COLOR_HSV2BGR_FULL, 0)
-            .expect("color converion failed");
-        let p: &VecN<f32, 3> = mat_output.at(0).expect("");
-        return Scalar::new(
+        cvt_color(&mat_input, &mut mat_output, COLOR_HSV2BGR_FULL, 0)?;
+        let p: &VecN<f32, 3> = mat_output.at(0)?;
+        return Ok(Scalar::new(
             (p[0] * 255.0).into(),
             (p[1] * 255.0).into(),
             (p[2] * 255.0).into(),
             0.,
-        );
-    }
-
-    fn get_mat_area(&mut self, reference_mat: &Mat) -> &Mat {
-        self.area.get_mat_area(reference_mat)
+        ));
     }
 }
 
@@ -67,87 +55,110 @@ pub struct ImageLogger {
     input_image: Mat,
     output_image: Mat,
     areas: HashMap<usize, ColoredArea>,
+    rng: StdRng,
 }
 
 impl ImageLogger {
-    pub fn new(image: Mat) -> Self {
-        let size = image.size().expect("image must be valied");
+    /// the colors are generated from `DEFAULT_SEED`, see `with_seed`
+    pub fn new(image: Mat) -> Result<Self> {
+        let size = image.size()?;
+        let mut rng = StdRng::seed_from_u64(DEFAULT_SEED);
         let mut areas = HashMap::new();
         areas.insert(
             0,
-            ColoredArea::new_random_color(Area::new(0, size.height, size.width)),
+            ColoredArea::new_random_color(Area::new(0, size.height, size.width), &mut rng)?,
         );
-        return ImageLogger {
+        return Ok(ImageLogger {
             input_image: image.clone(),
             output_image: image,
             areas,
-        };
+            rng,
+        });
+    }
+
+    /// seed used to generate the colors of the areas
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.set_seed(seed);
+        return self;
     }
     pub fn get_mat_ref(&self) -> &Mat {
         return &self.output_image;
     }
 
-    fn color_area(&mut self, id: usize) {
-        let area = self.areas.get_mut(&id).expect("index not found");
-        let color = area.color.clone();
-        let mask = area.get_mat_area(&self.input_image);
+    fn color_area(&mut self, id: usize) -> Result<()> {
+        let area = self
+            .areas
+            .get(&id)
+            .ok_or(anyhow!("item with id {id} not found"))?;
+
+        // only the bounding box of the area (plus the reach of the erode kernel) is redrawn
+        let bounding_box = area.area.marker.bounding_box();
+        let frame = rect_intersection(
+            Rect::new(
+                bounding_box.x - ERODE_KERNEL_SIZE,
+                bounding_box.y - ERODE_KERNEL_SIZE,
+                bounding_box.width + 2 * ERODE_KERNEL_SIZE,
+                bounding_box.height + 2 * ERODE_KERNEL_SIZE,
+            ),
+            Rect::new(0, 0, self.input_image.cols(), self.input_image.rows()),
+        )
+        .ok_or(anyhow!("area {id} is outside of the image"))?;
+        let mask = area.area.to_mask_in(frame)?;
+
+        let mut output_image = Mat::roi_mut(&mut self.output_image, frame)?;
+        let input_image = Mat::roi(&self.input_image, frame)?;
 
         // border color
         let color = Mat::new_rows_cols_with_default(
-            self.input_image.rows(),
-            self.input_image.cols(),
+            frame.height,
+            frame.width,
             self.input_image.typ(),
-            color,
-        )
-        .expect("matrix_creation_has_failed");
-        color
-            .copy_to_masked(&mut self.output_image, mask)
-            .expect("coloring has failed");
+            area.color,
+        )?;
+        color.copy_to_masked(&mut output_image, &mask)?;
         let mut second_mask = Mat::default();
 
         // real image
         opencv::imgproc::erode(
-            mask,
+            &mask,
             &mut second_mask,
-            &Mat::ones(4, 4, CV_8U).expect("matrix_creation_fail"),
+            &Mat::ones(ERODE_KERNEL_SIZE, ERODE_KERNEL_SIZE, CV_8U)?,
             opencv::core::Point_ { x: -1, y: -1 },
             1,
             BORDER_CONSTANT,
-            morphology_default_border_value().expect("unable to create border value"),
-        )
-        .expect("erosion has failed");
+            morphology_default_border_value()?,
+        )?;
 
-        self.input_image
-            .copy_to_masked(&mut self.output_image, &second_mask)
-            .expect("matrix copy has failed");
+        input_image.copy_to_masked(&mut output_image, &second_mask)?;
+        Ok(())
     }
 }
 
 impl LoggerTrait for ImageLogger {
-    fn log_split(&mut self, area_to_split_id: usize, splits: [Area; 2]) -> Result<()> {
+    fn log_split(&mut self, area_to_split_id: usize, splits: Vec<Area>) -> Result<()> {
         let old_area = self
             .areas
             .remove(&area_to_split_id)
             .ok_or(anyhow!("item with id{area_to_split_id} not found"))?;
 
-        let [a1, a2] = splits;
-        let [mut a1, a2]: [ColoredArea; 2] = [a1.into(), a2.into()];
-
-        let a1_id = a1.area.id;
-        let a2_id = a2.area.id;
-
-        // color consistency
-        a1.color = old_area.color;
-
-        if self.areas.get(&a1_id).is_some() || self.areas.get(&a2_id).is_some() {
-            return Err(anyhow!("item with specified id is already presetn"));
+        // the first split keeps the color of the old area, the others get a new one
+        let mut colored_splits = Vec::with_capacity(splits.len());
+        for (i, split) in splits.into_iter().enumerate() {
+            let colored_split = match i {
+                0 => ColoredArea::new(old_area.color, split),
+                _ => ColoredArea::new_random_color(split, &mut self.rng)?,
+            };
+            if self.areas.contains_key(&colored_split.area.id) {
+                return Err(anyhow!("item with specified id is already presetn"));
+            }
+            colored_splits.push(colored_split);
         }
 
-        self.areas.insert(a1_id, a1);
-        self.areas.insert(a2_id, a2);
-
-        self.color_area(a1_id);
-        self.color_area(a2_id);
+        for colored_split in colored_splits {
+            let id = colored_split.area.id;
+            self.areas.insert(id, colored_split);
+            self.color_area(id)?;
+        }
 
         Ok(())
     }
@@ -156,33 +167,38 @@ impl LoggerTrait for ImageLogger {
 
         let area_a = self
             .areas
-            .get_mut(&a)
+            .remove(&a)
             .ok_or(anyhow!("item not found: {a}"))?;
-        let area_a_color = area_a.color.clone();
-        let area_a = area_a.get_mat_area(&self.input_image) as *const Mat;
-
         let area_b = self
             .areas
-            .get_mut(&b)
-            .ok_or(anyhow!("item not found: {b}"))?
-            .get_mat_area(&self.input_image) as *const Mat;
-
-        // the reference are still valid since we haven't touch the hashmap
-        // (there is a mutable borrow only for the call to get_mat_area)
-        let area_a = unsafe { &*area_a };
-        let area_b = unsafe { &*area_b };
+            .remove(&b)
+            .ok_or(anyhow!("item not found: {b}"))?;
 
-        let marker = AreaMarker::merge(area_a, area_b)?;
+        let marker = AreaMarker::merge(area_a.area.marker, area_b.area.marker);
 
         let area = Area::new_from_id_and_marker(new_item_id, marker);
 
-        let area = ColoredArea::new(area_a_color, area);
+        let area = ColoredArea::new(area_a.color, area);
         self.areas.insert(new_item_id, area);
-        self.color_area(new_item_id);
+        self.color_area(new_item_id)?;
 
         Ok(())
     }
     fn finalize_log(&mut self) -> Result<()>  {
         Ok(())
     }
+
+    fn set_seed(&mut self, seed: u64) {
+        self.rng = StdRng::seed_from_u64(seed);
+        // the areas that already exist (only the whole image, if nothing has been logged yet)
+        // are colored again, in order of id
+        let mut ids = self.areas.keys().copied().collect::<Vec<_>>();
+        ids.sort();
+        for id in ids {
+            // the old color is kept if the new one can't be generated
+            if let Ok(color) = ColoredArea::get_random_color(&mut self.rng) {
+                self.areas.get_mut(&id).expect("id comes from the map").color = color;
+            }
+        }
+    }
 }
@@ -5,6 +5,7 @@ pub use null_logger::NullLogger;
 
 mod image_logger;
 pub use image_logger::ImageLogger;
+pub(crate) use image_logger::ColoredArea;
 
 mod on_screen_logger;
 pub use on_screen_logger::OnScreenLogger;
@@ -17,7 +18,28 @@ pub use on_disk_logger::OnDiskLogger;
 /// implementations including one that show the progress in rial time, one that save the result on
 /// a small video, an option that dose nothing and dose not impact performances eccetera
 pub trait LoggerTrait{
-    fn log_split(&mut self, area_to_split_id: usize, splits: [Area;2]) -> Result<()>;
+    fn log_split(&mut self, area_to_split_id: usize, splits: Vec<Area>) -> Result<()>;
     fn log_merge(&mut self, new_item_id: usize, to_merge: [usize;2]) -> Result<()>;
     fn finalize_log(&mut self) -> Result<()>;
+    /// seed of the random choices of the logger (like the colors of the areas), it is set by
+    /// `MainLogic::with_seed`. The loggers that don't use randomness can ignore it
+    fn set_seed(&mut self, _seed: u64) {}
+}
+
+impl LoggerTrait for Box<dyn LoggerTrait> {
+    fn log_split(&mut self, area_to_split_id: usize, splits: Vec<Area>) -> Result<()> {
+        return (**self).log_split(area_to_split_id, splits);
+    }
+
+    fn log_merge(&mut self, new_item_id: usize, to_merge: [usize;2]) -> Result<()> {
+        return (**self).log_merge(new_item_id, to_merge);
+    }
+
+    fn finalize_log(&mut self) -> Result<()> {
+        return (**self).finalize_log();
+    }
+
+    fn set_seed(&mut self, seed: u64) {
+        (**self).set_seed(seed);
+    }
 }
@@ -10,7 +10,7 @@ impl NullLogger {
     }
 }
 impl LoggerTrait for NullLogger {
-    fn log_split(&mut self, _area_to_split_id: usize, _splits: [super::Area;2]) -> anyhow::Result<()> {
+    fn log_split(&mut self, _area_to_split_id: usize, _splits: Vec<super::Area>) -> anyhow::Result<()> {
         Ok(())
     }
 
@@ -24,14 +24,14 @@ impl OnDiskLogger {
         video_writer.set(VideoWriterProperties::VIDEOWRITER_PROP_QUALITY as i32, 95.0)?;
 
         return Ok(Self {
-            image_logger: ImageLogger::new(image),
+            image_logger: ImageLogger::new(image)?,
             video_writer,
         });
     }
 }
 
 impl LoggerTrait for OnDiskLogger{
-    fn log_split(&mut self, area_to_split_id: usize, splits: [Area;2]) -> Result<()> {
+    fn log_split(&mut self, area_to_split_id: usize, splits: Vec<Area>) -> Result<()> {
         self.image_logger.log_split(area_to_split_id, splits)?;
         self.video_writer.write(&self.image_logger.get_mat_ref())?;
         return Ok(());
@@ -51,5 +51,8 @@ impl LoggerTrait for OnDiskLogger{
         self.video_writer.release()?;
         return Ok(())
     }
-}
 
+    fn set_seed(&mut self, seed: u64) {
+        self.image_logger.set_seed(seed);
+    }
+}
@@ -7,16 +7,16 @@ pub struct OnScreenLogger{
 }
 
 impl OnScreenLogger {
-    pub fn new(image: Mat, log_window_name: String) -> Self {
-        Self{
-            image_logger: ImageLogger::new(image),
+    pub fn new(image: Mat, log_window_name: String) -> Result<Self> {
+        Ok(Self{
+            image_logger: ImageLogger::new(image)?,
             log_window_name
-        }
+        })
     }
 }
 
 impl LoggerTrait for OnScreenLogger {
-    fn log_split(&mut self, area_to_split_id: usize, splits: [Area;2]) -> Result<()> {
+    fn log_split(&mut self, area_to_split_id: usize, splits: Vec<Area>) -> Result<()> {
         self.image_logger.log_split(area_to_split_id, splits)?;
         imshow(&self.log_window_name, self.image_logger.get_mat_ref())?;
         wait_key(0)?;
@@ -33,4 +33,8 @@ impl LoggerTrait for OnScreenLogger {
     fn finalize_log(&mut self) -> Result<()> {
         Ok(())
     }
+
+    fn set_seed(&mut self, seed: u64) {
+        self.image_logger.set_seed(seed);
+    }
 }
@@ -1,40 +1,58 @@
-mod image_container;
-mod logger_trait;
-mod main_logic;
-mod merger_trait;
-mod prelude;
-mod splitter_trait;
-use prelude::*;
+mod bench;
+mod cli;
+mod evaluate;
+use asymmetric_split_and_merge::prelude::*;
+use clap::Parser;
+use cli::{Cli, Command};
 
 use env_logger;
 
-fn main() {
+fn main() -> Result<()> {
+    let cli = Cli::parse();
+
     env_logger::Builder::new()
-        .filter_level(log::LevelFilter::Trace)
+        .filter_level(cli.log_level)
         .init();
 
-    let i = ImageContainer::new_from_file_color("./test_images/stuff.jpg")
-        .expect("test file must be present");
-    // let i = ImageContainer::new_from_file_color("./test_images/squares.jpg")
-    //     .expect("test file must be present");
-
-    // let splitter = splitter_traits::BlindSplitter::new(50);
-    // let splitter = splitter_traits::MaxDeltaSplitter::new(3,50.);
-    let splitter = splitter_traits::HeuristicAsymmetricSplitter::new(
-        splitter_traits::MaxDeltaSplitter::new(3,50.)
-    );
-    // let splitter = splitter_traits::BlindSplitter::new(10);
-    // let splitter = splitter_traits::StdSplitter::new(10, 30.);
-    // let merger = merger_traits::StdMerger::new(40.);
-    let merger = merger_traits::ColorBasedMerger::new(70.,250.);
-    let logger = logger_traits::OnDiskLogger::new(i.image.clone(), "./out.mp4")
-        .expect("can't create logger trait");
-    // let logger = logger_traits::OnScreenLogger::new(i.image.clone(), "log".into());
-    // let logger = logger_traits::NullLogger::new();
-
-     
-    let logic = MainLogic::new(splitter, merger, logger, &i);
-
-    let logic = logic.execute_split(10);
-    let _ = logic.execute_merge(10);
+    // the benchmarks build their own pool, sized on the numbers of workers they compare
+    return match &cli.command {
+        Some(Command::Evaluate(args)) => {
+            evaluate::evaluate_directory(&cli, args, &cli.build_pool()?)
+        }
+        Some(Command::Bench(args)) => bench::run_benchmarks(&cli, args),
+        None => segment(&cli, &cli.build_pool()?),
+    };
+}
+
+fn segment(cli: &Cli, pool: &WorkerPool) -> Result<()> {
+    let input = cli.input.as_deref().ok_or(anyhow!("the input image is required"))?;
+    let i = ImageContainer::new_from_file_color(input)?
+        .with_color_space(cli.get_color_space())?;
+
+    let logger = cli.build_logger(&i)?;
+    let result = cli.segment(&i, logger, pool)?;
+    info!("segmentation completed with {} regions", result.regions.len());
+
+    if let Some(path) = &cli.graph_output {
+        result.write_graph(path, cli.get_graph_format())?;
+        info!("region adjacency graph written to {path}");
+    }
+
+    if let Some(path) = &cli.polygon_output {
+        result.write_polygons(path, cli.get_polygon_format(), cli.polygon_tolerance)?;
+        info!("region polygons written to {path}");
+    }
+
+    if let Some(path) = &cli.coco_output {
+        let mut exporter = CocoExporter::new(cli.get_coco_segmentation());
+        exporter.add_image(input, &result)?;
+        exporter.write(path)?;
+        info!("COCO annotations written to {path}");
+    }
+
+    if let Some(writer) = cli.build_result_writer() {
+        writer.write(&result, &i)?;
+        info!("segmentation outputs written");
+    }
+    Ok(())
 }
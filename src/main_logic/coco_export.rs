@@ -0,0 +1,157 @@
+use super::*;
+use crate::json::json_string;
+
+/// how the mask of each region is stored in the COCO annotations
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum CocoSegmentation {
+    /// uncompressed run length encoding, it represents the mask exactly
+    #[default]
+    Rle,
+    /// outer polygons of the region (COCO polygons can't represent holes), simplified with the
+    /// given tolerance (see `SegmentationResult::get_region_polygons`). The regions that have no
+    /// polygon left after the simplification are stored with the run length encoding
+    Polygon { tolerance: f64 },
+}
+
+/// collects the segmentations of one or more images as COCO instance annotations, every region
+/// becomes an annotation of the single category `region`
+#[derive(Debug, Default)]
+pub struct CocoExporter {
+    segmentation: CocoSegmentation,
+    images: Vec<String>,
+    annotations: Vec<String>,
+}
+
+impl CocoExporter {
+    pub fn new(segmentation: CocoSegmentation) -> Self {
+        return Self {
+            segmentation,
+            images: Vec::new(),
+            annotations: Vec::new(),
+        };
+    }
+
+    /// add the regions of the segmentation of an image, and return the id given to the image
+    pub fn add_image(&mut self, file_name: &str, result: &SegmentationResult) -> Result<usize> {
+        let image_id = self.images.len() + 1;
+        let (width, height) = (result.labels.cols(), result.labels.rows());
+        self.images.push(format!(
+            "{{\"id\":{image_id},\"file_name\":{},\"width\":{width},\"height\":{height}}}",
+            json_string(file_name),
+        ));
+
+        let rle = |counts: &[usize]| {
+            let counts = counts.iter().map(|c| c.to_string()).collect::<Vec<_>>();
+            return format!("{{\"size\":[{height},{width}],\"counts\":[{}]}}", counts.join(","));
+        };
+        let segmentations = match self.segmentation {
+            CocoSegmentation::Rle => result
+                .get_rle_counts()?
+                .iter()
+                .map(|counts| rle(counts))
+                .collect::<Vec<_>>(),
+            CocoSegmentation::Polygon { tolerance } => {
+                let rle_counts = result.get_rle_counts()?;
+                let mut segmentations = Vec::with_capacity(result.regions.len());
+                for (region, counts) in result.regions.iter().zip(&rle_counts) {
+                    // the degenerate rings are already dropped, so every polygon has the 3 points
+                    // needed by COCO
+                    let polygons = result.get_region_polygons(region.id, tolerance)?;
+                    // a region with no polygon left would have an empty segmentation
+                    if polygons.is_empty() {
+                        segmentations.push(rle(counts));
+                        continue;
+                    }
+                    let polygons = polygons
+                        .into_iter()
+                        .map(|p| {
+                            let coordinates = p
+                                .exterior
+                                .iter()
+                                .map(|p| format!("{},{}", p.x, p.y))
+                                .collect::<Vec<_>>();
+                            format!("[{}]", coordinates.join(","))
+                        })
+                        .collect::<Vec<_>>();
+                    segmentations.push(format!("[{}]", polygons.join(",")));
+                }
+                segmentations
+            }
+        };
+
+        for (region, segmentation) in result.regions.iter().zip(segmentations) {
+            let bbox = region.bounding_box;
+            self.annotations.push(format!(
+                concat!(
+                    "{{\"id\":{},\"image_id\":{},\"category_id\":1,\"segmentation\":{},",
+                    "\"area\":{},\"bbox\":[{},{},{},{}],\"iscrowd\":0}}"
+                ),
+                self.annotations.len() + 1,
+                image_id,
+                segmentation,
+                region.pixel_count,
+                bbox.x,
+                bbox.y,
+                bbox.width,
+                bbox.height,
+            ));
+        }
+        return Ok(image_id);
+    }
+
+    pub fn to_json(&self) -> String {
+        return format!(
+            concat!(
+                "{{\"images\":[{}],\"annotations\":[{}],",
+                "\"categories\":[{{\"id\":1,\"name\":\"region\",\"supercategory\":\"region\"}}]}}\n"
+            ),
+            self.images.join(","),
+            self.annotations.join(","),
+        );
+    }
+
+    pub fn write(&self, path: &str) -> Result<()> {
+        return std::fs::write(path, self.to_json())
+            .with_context(|| format!("unable to write the COCO annotations to {path}"));
+    }
+}
+
+impl SegmentationResult {
+    /// run length encoding of the mask of every region, in the COCO format: the pixels are
+    /// scanned in column major order, and the runs alternate between pixels outside and inside
+    /// the region (starting from outside)
+    pub fn get_rle_counts(&self) -> Result<Vec<Vec<usize>>> {
+        let (width, height) = (self.labels.cols() as usize, self.labels.rows() as usize);
+        let labels = self.labels.data_typed::<i32>()?;
+
+        let mut counts = vec![Vec::new(); self.regions.len()];
+        // index (in column major order) following the last pixel of each region
+        let mut run_ends = vec![0; self.regions.len()];
+
+        for x in 0..width {
+            for y in 0..height {
+                let index = x * height + y;
+                let label = labels[y * width + x];
+                let region_counts = counts
+                    .get_mut(label as usize)
+                    .ok_or(anyhow!("pixel with invalid label {label}"))?;
+                let run_end = &mut run_ends[label as usize];
+
+                if !region_counts.is_empty() && index == *run_end {
+                    *region_counts.last_mut().expect("counts are not empty") += 1;
+                } else {
+                    region_counts.push(index - *run_end);
+                    region_counts.push(1);
+                }
+                *run_end = index + 1;
+            }
+        }
+
+        for (region_counts, run_end) in counts.iter_mut().zip(run_ends) {
+            if run_end < width * height {
+                region_counts.push(width * height - run_end);
+            }
+        }
+        return Ok(counts);
+    }
+}
@@ -2,16 +2,19 @@ use crate::prelude::*;
 use std::{
     borrow::{Borrow, BorrowMut},
     cell::RefCell,
-    collections::{hash_set::Iter, HashMap, HashSet},
+    collections::{btree_set::Iter, BTreeMap, BTreeSet, HashSet},
 };
 
+/// the items and their neighbors are kept in ordered containers, so that the couples to check
+/// (and therefore the result of the merge phase) don't depend on the hash ordering
 #[derive(Debug, Default)]
 pub struct DisjointSets {
-    items: HashMap<usize, DisjointSet>,
-    root_items: HashSet<usize>,
+    items: BTreeMap<usize, DisjointSet>,
+    root_items: BTreeSet<usize>,
 }
 
 impl DisjointSets {
+    /// the roots, sorted by id
     pub fn get_root_items(&self) -> Iter<'_, usize> {
         return self.root_items.iter();
     }
@@ -24,7 +27,7 @@ impl DisjointSets {
             if already_present_items.contains(a) {
                 continue;
             }
-            for b in self.items[a].inner.borrow_mut().neighbors.iter() {
+            for b in self.items[a].inner.borrow_mut().neighbors.keys() {
                 if already_present_items.contains(b) {
                     continue;
                 }
@@ -39,6 +42,17 @@ impl DisjointSets {
         return to_return;
     }
 
+    /// number of couples of roots that are still neighbors, each couple is counted once.
+    /// The neighbors of the roots must have been resolved with `clear_data`
+    pub fn count_neighbor_pairs(&self) -> usize {
+        let count = self
+            .root_items
+            .iter()
+            .map(|id| self.items[id].inner.borrow().neighbors.len())
+            .sum::<usize>();
+        return count / 2;
+    }
+
     pub fn get_set(&self, id: usize) -> Option<&DisjointSet> {
         return self.items.get(&id);
     }
@@ -51,22 +65,65 @@ impl DisjointSets {
         return self.root_items.contains(&id);
     }
 
+    /// roots of the neighbors of an item, sorted by id and without duplicates
+    pub fn get_root_neighbors(&self, id: usize) -> Result<Vec<usize>> {
+        let item = self
+            .items
+            .get(&id)
+            .ok_or(anyhow!("unable to find the id {id}"))?;
+        let root = item.get_father(self);
+
+        // collected before resolving the fathers, that could need to update this item
+        let keys = item.inner.borrow().neighbors.keys().copied().collect::<Vec<_>>();
+        let mut neighbors = Vec::new();
+        for neighbor in keys {
+            let father = self
+                .get_father_of(neighbor)
+                .ok_or(anyhow!("unable to find the id {neighbor}"))?;
+            if father != root {
+                neighbors.push(father);
+            }
+        }
+        neighbors.sort();
+        neighbors.dedup();
+        return Ok(neighbors);
+    }
+
+    /// length of the border shared by two neighbors, `None` if they are not neighbors
+    pub fn get_border_length(&self, a: usize, b: usize) -> Option<usize> {
+        return self.items.get(&a)?.inner.borrow().neighbors.get(&b).copied();
+    }
+
+    /// remove the two items from the neighbors of each other, including the keys that have not
+    /// been resolved yet (items that have since been merged in one of the two)
     pub fn mark_as_non_neighbors(&self, id1: usize, id2: usize) -> Result<()> {
-        self.items
-            .get(&id1)
-            .ok_or(anyhow!("item 1 not found"))?
-            .inner
-            .borrow_mut()
-            .neighbors
-            .remove(&id2);
+        let root1 = self.get_father_of(id1).ok_or(anyhow!("item 1 not found"))?;
+        let root2 = self.get_father_of(id2).ok_or(anyhow!("item 2 not found"))?;
+        self.remove_neighbors_with_root(id1, root2)?;
+        self.remove_neighbors_with_root(id2, root1)?;
+        Ok(())
+    }
 
-        self.items
-            .get(&id2)
-            .ok_or(anyhow!("item 2 not found"))?
-            .inner
-            .borrow_mut()
-            .neighbors
-            .remove(&id1);
+    fn remove_neighbors_with_root(&self, id: usize, root: usize) -> Result<()> {
+        let item = self
+            .items
+            .get(&id)
+            .ok_or(anyhow!("unable to find the id {id}"))?;
+        // collected before resolving the fathers, that could need to update this item
+        let keys = item.inner.borrow().neighbors.keys().copied().collect::<Vec<_>>();
+        let mut to_remove = Vec::new();
+        for neighbor in keys {
+            let father = self
+                .get_father_of(neighbor)
+                .ok_or(anyhow!("unable to find the id {neighbor}"))?;
+            if father == root {
+                to_remove.push(neighbor);
+            }
+        }
+        let neighbors = &mut item.inner.borrow_mut().neighbors;
+        for neighbor in to_remove {
+            neighbors.remove(&neighbor);
+        }
         Ok(())
     }
 
@@ -93,19 +150,30 @@ impl DisjointSets {
                 id: new_item_id,
                 inner: RefCell::new(DisjointSetInner {
                     father: new_item_id,
-                    neighbors: HashSet::new(),
+                    neighbors: BTreeMap::new(),
                 }),
             },
         );
 
-        let neighbors = self.items[&c1]
-            .inner
-            .borrow()
-            .neighbors
-            .iter()
-            .chain(self.items[&c2].inner.borrow().neighbors.iter())
-            .map(|x| *x)
-            .collect();
+        // the neighbors of the childrens are resolved to their roots, so that the maps of the
+        // roots stay compact while merging. The border with a neighbor of both the childrens is
+        // the sum of the two borders
+        let mut neighbors = BTreeMap::new();
+        for child in [c1, c2] {
+            // the childrens are no longer roots, so their neighbors won't be needed anymore
+            let child_neighbors = {
+                let mut inner = self.items[&child].inner.borrow_mut();
+                std::mem::take(&mut inner.neighbors)
+            };
+            for (id, border_length) in child_neighbors {
+                let father = self
+                    .get_father_of(id)
+                    .ok_or(anyhow!("unable to find the id {id}"))?;
+                if father != new_item_id {
+                    *neighbors.entry(father).or_insert(0) += border_length;
+                }
+            }
+        }
 
         self.items
             .get(&new_item_id)
@@ -131,7 +199,7 @@ impl DisjointSets {
                 id,
                 inner: DisjointSetInner {
                     father: id,
-                    neighbors: HashSet::new(),
+                    neighbors: BTreeMap::new(),
                 }
                 .into(),
             },
@@ -142,48 +210,48 @@ impl DisjointSets {
         return Ok(());
     }
 
-    pub fn set_as_neighbors(&mut self, a: usize, b: usize) -> Result<()> {
+    pub fn set_as_neighbors(&mut self, a: usize, b: usize, border_length: usize) -> Result<()> {
         self.items
             .get_mut(&a)
             .ok_or(anyhow!("unable to find item {a}"))?
             .inner
             .borrow_mut()
             .neighbors
-            .insert(b);
+            .insert(b, border_length);
         self.items
             .get_mut(&b)
             .ok_or(anyhow!("unable to find item {b}"))?
             .inner
             .borrow_mut()
             .neighbors
-            .insert(a);
+            .insert(a, border_length);
         Ok(())
     }
 
-    pub fn clear_data(&self) {
-        self.root_items.iter().for_each(|x| {
-            let new_neighbors = self.items[x]
-                .inner
-                .borrow()
-                .neighbors
-                .iter()
-                .map(|y| {
-                    self.get_father_of(*y) // take only the father
-                        .expect("error while cleaning data")
-                })
-                .filter(|z| {
-                    *z != self.items[x].id // remove reference to self
-                })
-                .collect();
+    pub fn clear_data(&self) -> Result<()> {
+        for x in self.root_items.iter() {
+            let mut new_neighbors = BTreeMap::new();
+            for (y, border_length) in self.items[x].inner.borrow().neighbors.iter() {
+                // take only the father
+                let father = self
+                    .get_father_of(*y)
+                    .ok_or(anyhow!("unable to find the id {y} while cleaning data"))?;
+                // remove reference to self
+                if father != self.items[x].id {
+                    *new_neighbors.entry(father).or_insert(0) += border_length;
+                }
+            }
             self.items[x].inner.borrow_mut().neighbors = new_neighbors;
-        });
+        }
+        Ok(())
     }
 }
 
 #[derive(Debug)]
 struct DisjointSetInner {
     father: usize,
-    neighbors: HashSet<usize>,
+    /// neighbors of the item, with the length of the border they share
+    neighbors: BTreeMap<usize, usize>,
 }
 #[derive(Debug)]
 pub struct DisjointSet {
@@ -192,15 +260,27 @@ pub struct DisjointSet {
 }
 
 impl DisjointSet {
+    /// find the root of the item, and make every item on the path point directly to it
     pub fn get_father(&self, others: &DisjointSets) -> usize {
-        let father = self.inner.borrow().father;
-        if self.id == father {
-            return self.id;
+        let mut root = self.inner.borrow().father;
+        if root == self.id {
+            return root;
+        }
+        loop {
+            let father = others.items[&root].inner.borrow().father;
+            if father == root {
+                break;
+            }
+            root = father;
         }
 
-        let new_father = others.items[&father].get_father(others);
-        self.set_father(new_father);
-        return new_father;
+        let mut item = self;
+        while item.id != root {
+            let father = item.inner.borrow().father;
+            item.set_father(root);
+            item = &others.items[&father];
+        }
+        return root;
     }
     pub fn set_father(&self, new_father: usize) {
         self.inner.borrow_mut().father = new_father;
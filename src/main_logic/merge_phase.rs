@@ -1,82 +1,290 @@
 use super::*;
+use std::{collections::BinaryHeap, time::Instant};
+
+/// number of couples evaluated by `MergeStrategy::BestFirst` between two progress reports
+const BEST_FIRST_PROGRESS_INTERVAL: usize = 256;
 
 impl<'a, S: SplitterTrait, M: MergerTrait, L: LoggerTrait> MainLogic<'a, S, M, L, MergeState> {
     pub fn execute_merge(
         mut self,
         num_of_workers: usize,
-    ) -> MainLogic<'a, S, M, L, CompleateState> {
-        self.initialize();
+    ) -> Result<MainLogic<'a, S, M, L, CompleateState>> {
+        // once the run is cancelled no area is merged, so the neighbors are not needed: only the
+        // areas and their statistics are initialized to build the result
+        if self.statistics.cancelled || self.cancellation_token.is_cancelled() {
+            info!("main thread: merge phase skipped, the run has been cancelled");
+            self.statistics.cancelled = true;
+            let start = Instant::now();
+            self.initialize_areas()
+                .context("initialization of the merge phase has failed")?;
+            self.statistics.initialization_time = start.elapsed();
+        } else {
+            let start = Instant::now();
+            self.initialize()
+                .context("initialization of the merge phase has failed")?;
+            self.statistics.initialization_time = start.elapsed();
+
+            let start = Instant::now();
+            match self.merge_strategy {
+                MergeStrategy::Greedy => self.merge_loop(num_of_workers),
+                MergeStrategy::BestFirst => self.best_first_merge_loop(num_of_workers),
+            }
+            .context("merge phase has failed")?;
+            self.statistics.merge_time = start.elapsed();
+        }
 
-        let join_handlers = self.launch_threads(num_of_workers);
+        self.logger.finalize_log()
+            .context("loggin finalization has failed")?;
+
+        let mut leafs = Vec::new();
+        self.split_tree[0].collect_leafs(&self.split_tree, &mut leafs);
+        let leafs = leafs.into_iter().map(|(id, _)| id).collect::<Vec<_>>();
+        let mut adjacent_leafs = Vec::new();
+        self.split_tree[0].collect_adjacent_leafs(&self.split_tree, &mut adjacent_leafs);
+        let mut result = SegmentationResult::new(
+            self.image,
+            &self.state.areas,
+            &leafs,
+            &self.state.disjoint_sets,
+            &adjacent_leafs,
+            |a, b| self.merger.score(self.get_statistics(a)?, self.get_statistics(b)?),
+        )
+        .context("error while building the segmentation result")?;
+        result.is_partial = self.statistics.cancelled;
+        result.seed = self.seed;
+
+        return Ok(MainLogic {
+            image: self.image,
+            pool: self.pool,
+            splitter: self.splitter,
+            merger: self.merger,
+            logger: self.logger,
+            split_tree: self.split_tree,
+            merge_strategy: self.merge_strategy,
+            statistics: self.statistics,
+            progress_callback: self.progress_callback,
+            cancellation_token: self.cancellation_token,
+            seed: self.seed,
+            state: CompleateState { result },
+        });
+    }
 
+    fn merge_loop(&mut self, num_of_workers: usize) -> Result<()> {
         loop {
-            info!("start sending split requests");
-            let num_requests = self.send_merge_request();
-            info!("main thread: sent {num_requests} requests");
+            let items_to_check = self.state.disjoint_sets.get_tuple_of_items_to_check();
+            info!("main thread: evaluating {} merge requests", items_to_check.len());
 
-            if num_requests == 0 {
-                break;
+            if items_to_check.is_empty() {
+                return Ok(());
             }
+            self.report_progress(Progress::Merge {
+                round: self.statistics.merge_rounds + 1,
+                accepted_merges: self.statistics.accepted_merges,
+                remaining_pairs: self.state.disjoint_sets.count_neighbor_pairs(),
+            });
+
+            let start = Instant::now();
+            let merge_results = self.evaluate_pairs(&items_to_check, num_of_workers, |merger, a, b| {
+                merger.merge(a, b)
+            })?;
+            self.statistics.pool_time += start.elapsed();
+
+            // the results of an interrupted round are discarded
+            let merge_results = match merge_results {
+                Some(merge_results) if !self.cancellation_token.is_cancelled() => merge_results,
+                _ => return self.cancel_merge(),
+            };
+            self.statistics.merge_rounds += 1;
+            self.statistics.merger_calls += items_to_check.len();
 
-            info!("start receiving split requests");
-            self.receive_merge_result(num_requests);
-            self.state.disjoint_sets.clear_data();
+            info!("main thread: applying merge results");
+            self.apply_merges(&items_to_check, merge_results)?;
+            self.state.disjoint_sets.clear_data()?;
         }
+    }
 
-        drop(self.state.images_to_merge_tx);
-        drop(self.state.merge_result_rx);
+    /// merge loop of `MergeStrategy::BestFirst`: the couples of neighbors are kept in a priority
+    /// queue ordered by score, and the most similar couple is always evaluated first
+    fn best_first_merge_loop(&mut self, num_of_workers: usize) -> Result<()> {
+        let roots = self.state.disjoint_sets.get_root_items().copied().collect::<Vec<_>>();
+        let mut candidates = Vec::new();
+        for a in roots {
+            for b in self.state.disjoint_sets.get_root_neighbors(a)? {
+                if a < b {
+                    candidates.push([a, b]);
+                }
+            }
+        }
 
-        info!("Start thread join");
-        join_handlers.into_iter().for_each(|x| {
-            let _ = x.join().expect("one of the threads has exited unsuccessfully");
+        info!("main thread: scoring {} merge candidates", candidates.len());
+        self.report_progress(Progress::Merge {
+            round: 1,
+            accepted_merges: 0,
+            remaining_pairs: candidates.len(),
         });
-        info!("Done thread join");
+        let start = Instant::now();
+        let scores = self.evaluate_pairs(&candidates, num_of_workers, |merger, a, b| {
+            merger.score(a, b)
+        })?;
+        self.statistics.pool_time += start.elapsed();
+        let scores = match scores {
+            Some(scores) if !self.cancellation_token.is_cancelled() => scores,
+            _ => return self.cancel_merge(),
+        };
+        self.statistics.merge_rounds += 1;
+        self.statistics.merger_calls += candidates.len();
+        let mut queue = candidates
+            .into_iter()
+            .zip(scores)
+            .map(|(ids, score)| ScoredPair { score, ids })
+            .collect::<BinaryHeap<_>>();
+
+        info!("main thread: start merging");
+        let mut evaluated = 0;
+        while let Some(ScoredPair { ids: [id_a, id_b], .. }) = queue.pop() {
+            if self.cancellation_token.is_cancelled() {
+                return self.cancel_merge();
+            }
+            evaluated += 1;
+            if evaluated % BEST_FIRST_PROGRESS_INTERVAL == 0 {
+                self.report_progress(Progress::Merge {
+                    round: self.statistics.merge_rounds,
+                    accepted_merges: self.statistics.accepted_merges,
+                    remaining_pairs: queue.len() + 1,
+                });
+            }
 
-        self.logger.finalize_log()
-            .expect("loggin finalization has failed");
+            // one of the two areas has already been merged with another neighbor
+            if !self.state.disjoint_sets.is_root_item(id_a)
+                || !self.state.disjoint_sets.is_root_item(id_b)
+            {
+                continue;
+            }
 
-        return MainLogic {
-            image: self.image,
-            splitter: self.splitter,
-            merger: self.merger,
-            logger: self.logger,
-            split_tree: self.split_tree,
-            state: CompleateState {},
-        };
+            let to_merge = self
+                .merger
+                .merge(self.get_statistics(id_a)?, self.get_statistics(id_b)?)
+                .with_context(|| format!("merger has failed on areas {id_a} and {id_b}"))?;
+            self.statistics.merger_calls += 1;
+            if !to_merge {
+                self.state.disjoint_sets.mark_as_non_neighbors(id_a, id_b)?;
+                self.statistics.rejected_merges += 1;
+                continue;
+            }
+
+            let new_item_id = self.merge_areas(id_a, id_b)?;
+
+            for neighbor in self.state.disjoint_sets.get_root_neighbors(new_item_id)? {
+                let score = self
+                    .merger
+                    .score(self.get_statistics(new_item_id)?, self.get_statistics(neighbor)?)
+                    .with_context(|| format!("merger has failed on areas {new_item_id} and {neighbor}"))?;
+                self.statistics.merger_calls += 1;
+                queue.push(ScoredPair {
+                    score,
+                    ids: [neighbor.min(new_item_id), neighbor.max(new_item_id)],
+                });
+            }
+        }
+
+        // the neighbors are only resolved lazily while merging
+        self.state.disjoint_sets.clear_data()?;
+        info!("main thread: merging completed");
+        Ok(())
+    }
+
+    /// stop the merge loop, the neighbors of the areas are resolved as at the end of a round
+    fn cancel_merge(&mut self) -> Result<()> {
+        info!("main thread: merge phase cancelled");
+        self.statistics.cancelled = true;
+        self.state.disjoint_sets.clear_data()?;
+        Ok(())
+    }
+
+    /// evaluate a function of the merger (on the worker pool) on each couple of areas.
+    /// The workers only borrow the statistics of the areas, that are not modified until all the
+    /// results are collected.
+    /// `None` is returned if the run has been cancelled while the couples were evaluated
+    fn evaluate_pairs<R, F>(
+        &self,
+        pairs: &[[usize; 2]],
+        num_of_workers: usize,
+        job: F,
+    ) -> Result<Option<Vec<R>>>
+    where
+        R: Send,
+        F: Fn(&M, &RegionStatistics, &RegionStatistics) -> Result<R> + Sync,
+    {
+        let merger = &self.merger;
+        let statistics = &self.state.statistics;
+        let cancellation_token = &self.cancellation_token;
+
+        let results = self
+            .pool
+            .parallel_map(num_of_workers, pairs, |&[id_a, id_b]| {
+                if cancellation_token.is_cancelled() {
+                    return Ok(None);
+                }
+                let stats_a = statistics
+                    .get(&id_a)
+                    .ok_or(anyhow!("statistics of area {id_a} not found"))?;
+                let stats_b = statistics
+                    .get(&id_b)
+                    .ok_or(anyhow!("statistics of area {id_b} not found"))?;
+
+                job(merger, stats_a, stats_b)
+                    .with_context(|| format!("merger has failed on areas {id_a} and {id_b}"))
+                    .map(Some)
+            })?
+            .into_iter()
+            .collect::<Result<Vec<_>>>()?;
+        return Ok(results.into_iter().collect());
+    }
+
+    fn get_statistics(&self, id: usize) -> Result<&RegionStatistics> {
+        return self
+            .state
+            .statistics
+            .get(&id)
+            .ok_or(anyhow!("statistics of area {id} not found"));
     }
 
-    pub fn send_merge_request(&mut self) -> usize {
-        let items_to_check = self.state.disjoint_sets.get_tuple_of_items_to_check(); 
-        let num_requests = items_to_check.len();
-        for [id_a,id_b] in items_to_check{
-                let mat_a = self
-                    .state
-                    .areas
-                    .get_mut(&id_a)
-                    .expect("error in area creation of merge phase")
-                    .get_mat_area(&self.image.image);
-                let mat_a = unsafe { UnmanagedMat::from_mat(mat_a) };
-
-                let mat_b = self
-                    .state
-                    .areas
-                    .get_mut(&id_b)
-                    .expect("error in area creation of merge phase")
-                    .get_mat_area(&self.image.image);
-                let mat_b = unsafe { UnmanagedMat::from_mat(mat_b) };
-
-                self.state
-                    .images_to_merge_tx
-                    .send((mat_a, id_a, mat_b, id_b))
-                    .expect("child threads has failed");
+    pub fn initialize(&mut self) -> Result<()> {
+        self.initialize_areas()?;
+
+        info!("start set as neighbors");
+        let mut adjacent_leafs = Vec::new();
+        self.split_tree[0].collect_adjacent_leafs(&self.split_tree, &mut adjacent_leafs);
+        for ([a_id, b_id], border_length) in adjacent_leafs {
+            self.state
+                .disjoint_sets
+                .set_as_neighbors(a_id, b_id, border_length)?;
         }
-        return num_requests;
+        info!("end set as neighbors");
+        Ok(())
     }
 
-    pub fn initialize(&mut self) {
+    /// every leaf of the split tree becomes an area (and a set of the disjoint sets)
+    fn initialize_areas(&mut self) -> Result<()> {
         let mut v = Vec::new();
         self.split_tree[0].collect_leafs(&self.split_tree, &mut v);
 
+        // the statistics of the leafs come from the integral images of the container,
+        // afterward they are only combined
+        let requires_texture = self.merger.requires_texture();
+        for (id, area) in &v {
+            let mut stats = area
+                .get_statistics()
+                .with_context(|| format!("unable to compute the statistics of area {id}"))?;
+            if requires_texture {
+                stats.texture = Some(
+                    area.get_texture_histogram()
+                        .with_context(|| format!("unable to compute the texture of area {id}"))?,
+                );
+            }
+            self.state.statistics.insert(*id, stats);
+        }
+
         let mut max_id = 0;
 
         for (id, area) in &v {
@@ -84,132 +292,77 @@ impl<'a, S: SplitterTrait, M: MergerTrait, L: LoggerTrait> MainLogic<'a, S, M, L
 
             self.state
                 .disjoint_sets
-                .add_item(*id)
-                .expect("something is wrong with the initialization of the merege state");
+                .add_item(*id)?;
             self.state
                 .areas
                 .insert(*id, Area::new_from_split(*id, area));
         }
         self.state.next_area_id = max_id + 1;
-
-        info!("start set as neighbors");
-        for i in 0..v.len() {
-            for j in (i + 1)..v.len() {
-                let (a_id, a_mat) = v[i];
-                let (b_id, b_mat) = v[j];
-                if ImageContainerSplit::are_neighbors(a_mat, b_mat) {
-                    // info!("set as neighbors {a_id} {b_id}");
-                    self.state
-                        .disjoint_sets
-                        .set_as_neighbors(a_id, b_id)
-                        .expect("something is wrong with the initialization of the merege state");
-                }
-            }
-        }
-        info!("end set as neighbors");
-    }
-
-    fn launch_threads(&self, num_of_workers: usize) -> Vec<JoinHandle<Result<()>>> {
-        let mut join_handlers = Vec::new();
-        for i in 0..num_of_workers {
-            let rx = self.state.images_to_merge_rx.clone();
-            let tx = self.state.merge_result_tx.clone();
-            // I am sure that this reference will stay valid for as long as the thread below exist
-            let merger: &'static M = unsafe { std::mem::transmute(&self.merger) };
-            // let image: &'static Mat = unsafe { std::mem::transmute(&self.image) };
-            let image = self.image.image.clone();
-            join_handlers.push(thread::spawn(move || -> Result<()> {
-                info!("thread {i} started");
-                loop {
-                    // info!("thread {i} rx lock");
-                    let rx_locked = rx.lock().map_err(|_| anyhow!("main tread has fail"))?;
-                    // info!("thread {i} rx locked");
-
-                    let (img_a, id_a, img_b, id_b) = rx_locked.recv()?;
-                    drop(rx_locked);
-                    // info!("thread {i} receive id=[{id_a},{id_b}]");
-
-                    // let merge_result = true;
-                    let merge_result = merger.merge(&img_a.image, &img_b.image, &image);
-                    // info!("thread {i} merge result = {:?}", merge_result);
-
-                    // info!("thread {i} tx lock");
-                    let tx_lock = tx.lock().map_err(|_| anyhow!("main tread has fail"))?;
-                    // info!("thread {i} tx locked");
-
-                    tx_lock.send((merge_result, id_a, id_b))
-                        .expect("main thread has reash");
-                    drop(tx_lock);
-
-                    img_a.destroy();
-                    img_b.destroy();
-                    // info!("thread {i} successfly processed id=[{id_a},{id_b}]");
-                }
-            }));
-        }
-        return join_handlers;
+        Ok(())
     }
 
-    fn receive_merge_result(&mut self, to_receive: usize) {
+    fn apply_merges(&mut self, items_to_check: &[[usize; 2]], merge_results: Vec<bool>) -> Result<()> {
         let mut to_merge_vec = Vec::new();
-        for _ in 0..to_receive {
-
-            let (to_merge, id_a, id_b) = self
-                .state
-                .merge_result_rx
-                .recv()
-                .expect("child thread has fail");
-    
+        for ([id_a, id_b], to_merge) in items_to_check.iter().copied().zip(merge_results) {
             if !to_merge {
-                self.state.disjoint_sets.mark_as_non_neighbors(id_a, id_b)
-                .expect("receive_merge_result failed");
-            }else {
-                to_merge_vec.push([id_a,id_b]);
+                self.state.disjoint_sets.mark_as_non_neighbors(id_a, id_b)?;
+                self.statistics.rejected_merges += 1;
+            } else {
+                to_merge_vec.push([id_a, id_b]);
             };
-            
         }
 
         for [id_a, id_b] in to_merge_vec {
-
-            // assert!(self.state.disjoint_sets.is_root_item(id_a));
-            // assert!(self.state.disjoint_sets.is_root_item(id_b));
-
-            let new_item_id = self.state.next_area_id; 
-            self.state.next_area_id += 1;
-
-            let area_a = self
-                .state
-                .areas
-                .get_mut(&id_a)
-                .expect("error while reading mask")
-                .get_mat_area(&self.image.image) as *const Mat;
-            let area_b = self
-                .state
-                .areas
-                .get_mut(&id_b)
-                .expect("error while reading mask")
-                .get_mat_area(&self.image.image) as *const Mat;
-
-            // the reference are still valid since we haven't touch the hashmap
-            // (there is a mutable borrow only for the call to get_mat_area)
-            let area_a = unsafe { &*area_a };
-            let area_b = unsafe { &*area_b };
-
-
-            let marker =
-                AreaMarker::merge(area_a, area_b).expect("error in creation of merge marker");
-
-            let area = Area::new_from_id_and_marker(new_item_id, marker);
-
-            self.logger.log_merge(new_item_id, [id_a,id_b])
-                .expect("logger has failed");
-
-            self.state.areas.insert(new_item_id, area);
-
-            self.state
-                .disjoint_sets
-                .create_new(new_item_id, [id_a, id_b])
-                .expect("error in recieve merge result");
+            self.merge_areas(id_a, id_b)?;
         }
+        Ok(())
+    }
+
+    /// merge two root areas into a new one, and return the id of the new area
+    fn merge_areas(&mut self, id_a: usize, id_b: usize) -> Result<usize> {
+        let new_item_id = self.state.next_area_id;
+        self.state.next_area_id += 1;
+        self.statistics.accepted_merges += 1;
+
+        // the merged areas are no longer roots, so they won't be needed anymore
+        let area_a = self
+            .state
+            .areas
+            .remove(&id_a)
+            .ok_or(anyhow!("area {id_a} not found"))?;
+        let area_b = self
+            .state
+            .areas
+            .remove(&id_b)
+            .ok_or(anyhow!("area {id_b} not found"))?;
+
+        let stats_a = self
+            .state
+            .statistics
+            .remove(&id_a)
+            .ok_or(anyhow!("statistics of area {id_a} not found"))?;
+        let stats_b = self
+            .state
+            .statistics
+            .remove(&id_b)
+            .ok_or(anyhow!("statistics of area {id_b} not found"))?;
+
+        let marker = AreaMarker::merge(area_a.marker, area_b.marker);
+        self.state
+            .statistics
+            .insert(new_item_id, RegionStatistics::merge(&stats_a, &stats_b));
+
+        let area = Area::new_from_id_and_marker(new_item_id, marker);
+
+        self.logger.log_merge(new_item_id, [id_a,id_b])
+            .context("logger has failed")?;
+
+        self.state.areas.insert(new_item_id, area);
+
+        self.state
+            .disjoint_sets
+            .create_new(new_item_id, [id_a, id_b])?;
+
+        return Ok(new_item_id);
     }
 }
@@ -0,0 +1,46 @@
+use std::cmp::Ordering;
+
+/// order in which the couples of neighbor areas are evaluated during the merge phase
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum MergeStrategy {
+    /// in each round every area is paired with one of its neighbors, and all the couples are
+    /// evaluated in parallel
+    #[default]
+    Greedy,
+    /// the couple of neighbors with the lowest `MergerTrait::score` is always evaluated first.
+    /// The result does not depend on the order of the hash containers, but only the initial
+    /// scoring is executed in parallel
+    BestFirst,
+}
+
+/// candidate couple of areas in the priority queue of `MergeStrategy::BestFirst`.
+/// The ordering is reversed, so that `BinaryHeap` pops the lowest score first, and ties
+/// are broken by the ids of the areas
+#[derive(Debug, Clone, Copy)]
+pub(super) struct ScoredPair {
+    pub score: f64,
+    pub ids: [usize; 2],
+}
+
+impl PartialEq for ScoredPair {
+    fn eq(&self, other: &Self) -> bool {
+        return self.cmp(other) == Ordering::Equal;
+    }
+}
+
+impl Eq for ScoredPair {}
+
+impl PartialOrd for ScoredPair {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        return Some(self.cmp(other));
+    }
+}
+
+impl Ord for ScoredPair {
+    fn cmp(&self, other: &Self) -> Ordering {
+        return other
+            .score
+            .total_cmp(&self.score)
+            .then_with(|| other.ids.cmp(&self.ids));
+    }
+}
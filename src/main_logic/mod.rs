@@ -4,61 +4,56 @@ mod split_phase;
 mod split_tree;
 mod merge_phase;
 mod disjoint_set;
+mod segmentation_result;
+mod merge_strategy;
+mod region_adjacency_graph;
+mod result_writer;
+mod polygon_export;
+mod coco_export;
+mod run_statistics;
+mod progress;
+
+/// seed used when no seed is given with `MainLogic::with_seed`
+pub const DEFAULT_SEED: u64 = 0;
 
 use disjoint_set::*;
 use split_tree::*;
+pub use segmentation_result::*;
+pub use merge_strategy::MergeStrategy;
+pub use region_adjacency_graph::*;
+pub use result_writer::*;
+pub use polygon_export::*;
+pub use coco_export::*;
+pub use run_statistics::RunStatistics;
+pub use progress::{CancellationToken, Progress};
+use merge_strategy::ScoredPair;
 
 use crate::prelude::*;
-use std::{ collections::{HashMap, HashSet}, sync::{mpsc::{channel, Receiver, Sender}, Arc, Mutex}, thread::{self, JoinHandle}};
+use anyhow::Context;
+use std::collections::{HashMap, HashSet};
 
 
 
 pub struct SplitState {
     yet_to_split_images: Vec<usize>,
-    images_to_split_tx: Sender<(UnmanagedMat, usize)>,
-    images_to_split_rx: Arc<Mutex<Receiver<(UnmanagedMat, usize)>>>,
-    split_result_tx: Arc<Mutex<Sender<Option<(CutDirection, i32, usize)>>>>,
-    split_result_rx: Receiver<Option<(CutDirection, i32, usize)>>,
-    items_in_queue: usize,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Default)]
 pub struct MergeState {
     disjoint_sets: DisjointSets,
-    images_to_merge_tx: Sender<(UnmanagedMat, usize, UnmanagedMat, usize)>,
-    images_to_merge_rx: Arc<Mutex<Receiver<(UnmanagedMat, usize, UnmanagedMat, usize)>>>,
-    merge_result_tx: Arc<Mutex<Sender<(bool, usize, usize)>>>,
-    merge_result_rx: Receiver<(bool, usize, usize)>,
     already_checked_mgerges: HashSet<[usize;2]>,
+    /// rectangles of the areas that are still roots of the disjoint sets, the regions of the
+    /// result are built from them
     areas: HashMap<usize, Area>,
+    /// running statistics of the areas that are still roots of the disjoint sets
+    statistics: HashMap<usize, RegionStatistics>,
     next_area_id: usize,
 }
 
-impl Default for MergeState {
-    
-    fn default() -> Self {
-
-        let (images_to_merge_tx, images_to_merge_rx) = channel();
-        let (merge_result_tx, merge_result_rx) = channel();
-
-        let images_to_merge_rx = Arc::new(Mutex::new(images_to_merge_rx));
-        let merge_result_tx = Arc::new(Mutex::new(merge_result_tx));
-       
-        Self{
-            images_to_merge_tx,
-            images_to_merge_rx,
-            merge_result_tx,
-            merge_result_rx,
-            next_area_id: 0,
-            disjoint_sets: DisjointSets::default(),
-            already_checked_mgerges: HashSet::default(),
-            areas: HashMap::default()
-        }
-    }
+pub struct CompleateState {
+    pub result: SegmentationResult,
 }
 
-pub struct CompleateState {}
-
 pub trait SplitMergeState {}
 impl SplitMergeState for SplitState {}
 impl SplitMergeState for MergeState {}
@@ -68,14 +63,73 @@ impl SplitMergeState for CompleateState {}
 /// merge algorithm.
 /// All the code that can vary from an implementation to another is encapsulated in the 3 generic
 /// types `S`, `M` and `L`
+/// The work is executed on a WorkerPool, that can be shared with other instances of the main logic
 pub struct MainLogic<'a, S: SplitterTrait, M: MergerTrait, L: LoggerTrait, ST: SplitMergeState> {
     splitter: S,
     merger: M,
     logger: L,
     state: ST,
     image: &'a ImageContainer,
+    pool: &'a WorkerPool,
     split_tree: Vec<SplitTree<'a>>,
+    merge_strategy: MergeStrategy,
+    statistics: RunStatistics,
+    progress_callback: Option<Box<dyn FnMut(Progress) + 'a>>,
+    cancellation_token: CancellationToken,
+    seed: u64,
+}
+
+impl<'a, S: SplitterTrait, M: MergerTrait, L: LoggerTrait, ST: SplitMergeState> MainLogic<'a, S, M, L, ST> {
+    /// select the order in which the couples of neighbor areas are evaluated (`Greedy` by default)
+    pub fn with_merge_strategy(mut self, merge_strategy: MergeStrategy) -> Self {
+        self.merge_strategy = merge_strategy;
+        return self;
+    }
+
+    /// function called by the main thread after every round of the split and merge phases
+    pub fn with_progress_callback(mut self, callback: impl FnMut(Progress) + 'a) -> Self {
+        self.progress_callback = Some(Box::new(callback));
+        return self;
+    }
+
+    /// token that can be used to stop the run early, the phases still complete successfully
+    /// but `SegmentationResult::is_partial` is set
+    pub fn with_cancellation_token(mut self, cancellation_token: CancellationToken) -> Self {
+        self.cancellation_token = cancellation_token;
+        return self;
+    }
+
+    /// seed of the random colors: it is given to the logger (see `LoggerTrait::set_seed`) and
+    /// stored in the result, where it is used for the colors of the false color overlay.
+    /// The segmentation itself does not depend on it, runs with the same parameters give the
+    /// same areas independently from the seed and the number of workers
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self.logger.set_seed(seed);
+        return self;
+    }
+
+    fn report_progress(&mut self, progress: Progress) {
+        if let Some(callback) = &mut self.progress_callback {
+            callback(progress);
+        }
+    }
 }
 
+impl<'a, S: SplitterTrait, M: MergerTrait, L: LoggerTrait> MainLogic<'a, S, M, L, CompleateState> {
+    pub fn get_result(&self) -> &SegmentationResult {
+        return &self.state.result;
+    }
 
+    pub fn into_result(self) -> SegmentationResult {
+        return self.state.result;
+    }
+
+    pub fn get_run_statistics(&self) -> &RunStatistics {
+        return &self.statistics;
+    }
 
+    pub fn into_result_and_statistics(self) -> (SegmentationResult, RunStatistics) {
+        return (self.state.result, self.statistics);
+    }
+}
@@ -0,0 +1,315 @@
+use opencv::{
+    core::{compare, Point, Scalar, Vector, CMP_EQ, CV_32S},
+    imgproc::{approx_poly_dp, connected_components},
+};
+use std::collections::BTreeMap;
+
+use super::*;
+
+/// a polygon with holes, the coordinates are in pixels with the y axis pointing down.
+/// The rings follow the edges of the pixels: the pixel (x, y) is the square between the corners
+/// (x, y) and (x + 1, y + 1).
+/// The exterior ring has a positive signed area (counterclockwise once the y axis is pointing up,
+/// as required by geojson) and the holes have a negative one
+#[derive(Debug, Clone, PartialEq)]
+pub struct Polygon {
+    pub exterior: Vec<Point>,
+    pub holes: Vec<Vec<Point>>,
+}
+
+/// file format used to export the polygons of the regions
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum PolygonFormat {
+    #[default]
+    GeoJson,
+    Svg,
+}
+
+impl SegmentationResult {
+    /// vectorize a region into polygons (one for each 4-connected component of the region).
+    /// With a `tolerance` greater than 0 the polygons are simplified with the Douglas-Peucker
+    /// algorithm, no vertex is moved by more than `tolerance` pixels. The rings that are
+    /// reduced to less than 3 points or to no area are dropped, together with the holes of a
+    /// dropped exterior ring
+    pub fn get_region_polygons(&self, region_id: usize, tolerance: f64) -> Result<Vec<Polygon>> {
+        let region = self
+            .regions
+            .get(region_id)
+            .ok_or(anyhow!("region {region_id} not found"))?;
+        let bounding_box = region.bounding_box;
+
+        // only the bounding box of the region needs to be traced
+        let mut mask = Mat::default();
+        compare(
+            &Mat::roi(&self.labels, bounding_box)?,
+            &Scalar::all(region_id as f64),
+            &mut mask,
+            CMP_EQ,
+        )?;
+
+        let mut components = Mat::default();
+        connected_components(&mask, &mut components, 4, CV_32S)?;
+        let components = components.data_typed::<i32>()?;
+        let component_of = |pixel: Point| {
+            let pixel = pixel - bounding_box.tl();
+            return components[(pixel.y * bounding_box.width + pixel.x) as usize];
+        };
+
+        // every component has exactly one exterior ring, the holes are attached to it once
+        // all the rings are traced
+        let mut polygons = Vec::new();
+        let mut component_to_polygon = HashMap::new();
+        let mut holes = Vec::new();
+        for ring in trace_pixel_edges(&mask, bounding_box.tl())? {
+            let component = component_of(ring.inner_pixel);
+            if signed_area(&ring.corners) > 0 {
+                component_to_polygon.insert(component, polygons.len());
+                polygons.push(Polygon {
+                    exterior: simplify(&ring.corners, tolerance)?,
+                    holes: Vec::new(),
+                });
+            } else {
+                holes.push((component, simplify(&ring.corners, tolerance)?));
+            }
+        }
+        for (component, hole) in holes {
+            let polygon = component_to_polygon
+                .get(&component)
+                .ok_or(anyhow!("a hole of region {region_id} has no exterior ring"))?;
+            polygons[*polygon].holes.push(hole);
+        }
+
+        // the simplification can collapse a ring, and it does not preserve the orientation
+        let mut polygons = polygons
+            .into_iter()
+            .filter(|p| !is_degenerate(&p.exterior))
+            .collect::<Vec<_>>();
+        for polygon in &mut polygons {
+            polygon.holes.retain(|hole| !is_degenerate(hole));
+            if signed_area(&polygon.exterior) < 0 {
+                polygon.exterior.reverse();
+            }
+            for hole in &mut polygon.holes {
+                if signed_area(hole) > 0 {
+                    hole.reverse();
+                }
+            }
+        }
+        return Ok(polygons);
+    }
+
+    /// serialize the polygons of all the regions
+    pub fn export_polygons(&self, format: PolygonFormat, tolerance: f64) -> Result<String> {
+        let mut polygons = Vec::with_capacity(self.regions.len());
+        for region in &self.regions {
+            polygons.push(self.get_region_polygons(region.id, tolerance)?);
+        }
+        return Ok(match format {
+            PolygonFormat::GeoJson => self.polygons_to_geojson(&polygons),
+            PolygonFormat::Svg => self.polygons_to_svg(&polygons),
+        });
+    }
+
+    pub fn write_polygons(&self, path: &str, format: PolygonFormat, tolerance: f64) -> Result<()> {
+        return std::fs::write(path, self.export_polygons(format, tolerance)?)
+            .with_context(|| format!("unable to write the polygons to {path}"));
+    }
+
+    /// a `FeatureCollection` with a `MultiPolygon` feature for each region
+    fn polygons_to_geojson(&self, polygons: &[Vec<Polygon>]) -> String {
+        let features = self
+            .regions
+            .iter()
+            .zip(polygons)
+            .map(|(region, polygons)| {
+                let coordinates = polygons
+                    .iter()
+                    .map(|polygon| {
+                        let rings = std::iter::once(&polygon.exterior)
+                            .chain(&polygon.holes)
+                            .map(|ring| geojson_ring(ring))
+                            .collect::<Vec<_>>();
+                        format!("[{}]", rings.join(","))
+                    })
+                    .collect::<Vec<_>>();
+                format!(
+                    concat!(
+                        "{{\"type\":\"Feature\",\"properties\":{{\"id\":{},\"area\":{}}},",
+                        "\"geometry\":{{\"type\":\"MultiPolygon\",\"coordinates\":[{}]}}}}"
+                    ),
+                    region.id,
+                    region.pixel_count,
+                    coordinates.join(","),
+                )
+            })
+            .collect::<Vec<_>>();
+        return format!(
+            "{{\"type\":\"FeatureCollection\",\"features\":[{}]}}\n",
+            features.join(",")
+        );
+    }
+
+    /// a path for each region, filled with the mean color of the region
+    fn polygons_to_svg(&self, polygons: &[Vec<Polygon>]) -> String {
+        let (width, height) = (self.labels.cols(), self.labels.rows());
+        let mut out = format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{width}\" height=\"{height}\" viewBox=\"0 0 {width} {height}\">\n"
+        );
+        for (region, polygons) in self.regions.iter().zip(polygons) {
+            let path = polygons
+                .iter()
+                .flat_map(|polygon| std::iter::once(&polygon.exterior).chain(&polygon.holes))
+                .map(|ring| svg_ring(ring))
+                .collect::<Vec<_>>();
+            // the mean color is in BGR order
+            let [b, g, r] = [0, 1, 2].map(|c| region.mean_color[c].round() as u8);
+            out.push_str(&format!(
+                "  <path id=\"region_{}\" d=\"{}\" fill=\"rgb({r},{g},{b})\" fill-rule=\"evenodd\"/>\n",
+                region.id,
+                path.join(" "),
+            ));
+        }
+        out.push_str("</svg>\n");
+        return out;
+    }
+}
+
+/// a closed ring traced along the edges of the pixels of a mask
+struct TracedRing {
+    /// only the corners where the direction changes
+    corners: Vec<Point>,
+    /// a pixel of the mask next to the ring
+    inner_pixel: Point,
+}
+
+/// trace the borders of the pixels of a `CV_8U` mask along the edges of the pixels.
+/// The rings are traced with the mask on their right (with the y axis pointing down), so the
+/// exterior rings have a positive signed area, and the holes a negative one.
+/// Where two pixels of the mask touch only at a corner the rings turn right, so the two pixels
+/// are traced as parts of different rings
+fn trace_pixel_edges(mask: &Mat, offset: Point) -> Result<Vec<TracedRing>> {
+    let (rows, cols) = (mask.rows(), mask.cols());
+    let data = mask.data_typed::<u8>()?;
+    let inside = |x: i32, y: i32| {
+        return x >= 0 && y >= 0 && x < cols && y < rows && data[(y * cols + x) as usize] != 0;
+    };
+
+    // map from a corner to the ends of the edges that start from it
+    let mut edges = BTreeMap::<(i32, i32), Vec<(i32, i32)>>::new();
+    for y in 0..rows {
+        for x in 0..cols {
+            if !inside(x, y) {
+                continue;
+            }
+            let mut add_edge = |from, to| edges.entry(from).or_default().push(to);
+            if !inside(x, y - 1) {
+                add_edge((x, y), (x + 1, y));
+            }
+            if !inside(x + 1, y) {
+                add_edge((x + 1, y), (x + 1, y + 1));
+            }
+            if !inside(x, y + 1) {
+                add_edge((x + 1, y + 1), (x, y + 1));
+            }
+            if !inside(x - 1, y) {
+                add_edge((x, y + 1), (x, y));
+            }
+        }
+    }
+
+    let mut rings = Vec::new();
+    while let Some((&start, _)) = edges.first_key_value() {
+        let mut corners = Vec::new();
+        let mut inner_pixel = None;
+        let (mut from, mut direction) = (start, (0, 0));
+        loop {
+            let to = take_edge(&mut edges, from, direction)
+                .ok_or(anyhow!("the border of the mask is not closed at {from:?}"))?;
+            let new_direction = (to.0 - from.0, to.1 - from.1);
+            if new_direction != direction {
+                corners.push(Point::new(from.0, from.1) + offset);
+            }
+            // the pixel on the right of the first edge
+            if inner_pixel.is_none() {
+                inner_pixel = Some(Point::new(
+                    from.0.min(to.0) - (new_direction.1 > 0) as i32,
+                    from.1.min(to.1) - (new_direction.0 < 0) as i32,
+                ) + offset);
+            }
+            (from, direction) = (to, new_direction);
+            if from == start {
+                break;
+            }
+        }
+        // the ring can start in the middle of a side
+        if corners.len() > 1 {
+            let [first, second, last] = [corners[0], corners[1], corners[corners.len() - 1]];
+            let (a, b) = (second - first, last - first);
+            if a.x * b.y - a.y * b.x == 0 {
+                corners.remove(0);
+            }
+        }
+        rings.push(TracedRing {
+            corners,
+            inner_pixel: inner_pixel.ok_or(anyhow!("empty ring"))?,
+        });
+    }
+    return Ok(rings);
+}
+
+/// remove the edge that starts from `from`, and return its end. When two edges start from the
+/// same corner the one that turns right (coming from `direction`) is taken
+fn take_edge(
+    edges: &mut BTreeMap<(i32, i32), Vec<(i32, i32)>>,
+    from: (i32, i32),
+    direction: (i32, i32),
+) -> Option<(i32, i32)> {
+    let ends = edges.get_mut(&from)?;
+    let right_turn = (from.0 - direction.1, from.1 + direction.0);
+    let index = ends.iter().position(|end| *end == right_turn).unwrap_or(0);
+    let end = ends.swap_remove(index);
+    if ends.is_empty() {
+        edges.remove(&from);
+    }
+    return Some(end);
+}
+
+/// twice the signed area of a ring (shoelace formula)
+fn signed_area(ring: &[Point]) -> i64 {
+    return ring
+        .iter()
+        .zip(ring.iter().cycle().skip(1))
+        .map(|(a, b)| a.x as i64 * b.y as i64 - b.x as i64 * a.y as i64)
+        .sum();
+}
+
+fn is_degenerate(ring: &[Point]) -> bool {
+    return ring.len() < 3 || signed_area(ring) == 0;
+}
+
+fn simplify(ring: &[Point], tolerance: f64) -> Result<Vec<Point>> {
+    if tolerance <= 0. {
+        return Ok(ring.to_vec());
+    }
+    let mut simplified = Vector::<Point>::new();
+    approx_poly_dp(&Vector::from_slice(ring), &mut simplified, tolerance, true)?;
+    return Ok(simplified.to_vec());
+}
+
+/// geojson rings must be closed, with the first point repeated at the end
+fn geojson_ring(ring: &[Point]) -> String {
+    let points = ring
+        .iter()
+        .chain(ring.first())
+        .map(|p| format!("[{},{}]", p.x, p.y))
+        .collect::<Vec<_>>();
+    return format!("[{}]", points.join(","));
+}
+
+fn svg_ring(ring: &[Point]) -> String {
+    let points = ring
+        .iter()
+        .map(|p| format!("{} {}", p.x, p.y))
+        .collect::<Vec<_>>();
+    return format!("M {} Z", points.join(" L "));
+}
@@ -0,0 +1,64 @@
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::{Duration, Instant},
+};
+
+/// progress of a run, reported to the callback set with `MainLogic::with_progress_callback`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Progress {
+    /// a round of split requests has been applied
+    Split {
+        round: usize,
+        /// number of areas split since the beginning of the split phase
+        split_areas: usize,
+        /// number of areas that will be evaluated in the next round
+        pending: usize,
+    },
+    /// a round of merge requests is about to be evaluated (with `MergeStrategy::BestFirst` it is
+    /// reported periodically while the queue is consumed)
+    Merge {
+        round: usize,
+        /// number of merges accepted since the beginning of the merge phase
+        accepted_merges: usize,
+        /// number of couples of neighbor areas that are still candidates for a merge, including
+        /// the ones evaluated in the current round
+        remaining_pairs: usize,
+    },
+}
+
+/// token used to stop a run from another thread. The main loop stops at the end of the current
+/// round (the workers skip the requests that have not been started yet) and the result contains
+/// the areas as they were at that point
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken {
+    cancelled: Arc<AtomicBool>,
+    /// the token is cancelled on its own once this instant is reached
+    deadline: Option<Instant>,
+}
+
+impl CancellationToken {
+    pub fn new() -> Self {
+        return Self::default();
+    }
+
+    /// cancel the token once `time_limit` has elapsed from now. No thread is involved, the
+    /// deadline is checked every time the token is polled
+    pub fn with_time_limit(mut self, time_limit: Duration) -> Self {
+        self.deadline = Instant::now().checked_add(time_limit);
+        return self;
+    }
+
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        if self.cancelled.load(Ordering::Relaxed) {
+            return true;
+        }
+        return self.deadline.is_some_and(|deadline| Instant::now() >= deadline);
+    }
+}
@@ -0,0 +1,159 @@
+use std::fmt::Write;
+
+use super::*;
+use crate::json::json_number;
+
+/// an edge of the region adjacency graph, between two regions that share a border
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RegionEdge {
+    /// ids of the two regions, the lower id comes first
+    pub regions: [usize; 2],
+    /// number of pixels along the border shared by the two regions
+    pub border_length: usize,
+    /// `MergerTrait::score` of the two regions
+    pub distance: f64,
+}
+
+/// file format used to export the region adjacency graph
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum GraphFormat {
+    #[default]
+    Dot,
+    GraphMl,
+    Json,
+}
+
+impl SegmentationResult {
+    /// serialize the region adjacency graph (the regions as nodes, and the couples of regions that
+    /// share a border as edges)
+    pub fn export_graph(&self, format: GraphFormat) -> String {
+        return match format {
+            GraphFormat::Dot => self.graph_to_dot(),
+            GraphFormat::GraphMl => self.graph_to_graphml(),
+            GraphFormat::Json => self.graph_to_json(),
+        };
+    }
+
+    pub fn write_graph(&self, path: &str, format: GraphFormat) -> Result<()> {
+        return std::fs::write(path, self.export_graph(format))
+            .with_context(|| format!("unable to write the region adjacency graph to {path}"));
+    }
+
+    fn graph_to_dot(&self) -> String {
+        let mut out = String::from("graph regions {\n");
+        for r in &self.regions {
+            let _ = writeln!(
+                out,
+                "  {} [area={}, centroid_x={}, centroid_y={}, mean_color=\"{}\"];",
+                r.id,
+                r.pixel_count,
+                r.centroid.x,
+                r.centroid.y,
+                mean_color_as_list(r).join(","),
+            );
+        }
+        for e in &self.edges {
+            // dot has no representation for infinite and nan values, so the attribute is omitted
+            let distance = if e.distance.is_finite() {
+                format!(", distance={}", e.distance)
+            } else {
+                String::new()
+            };
+            let _ = writeln!(
+                out,
+                "  {} -- {} [border_length={}{}];",
+                e.regions[0], e.regions[1], e.border_length, distance,
+            );
+        }
+        out.push_str("}\n");
+        return out;
+    }
+
+    fn graph_to_graphml(&self) -> String {
+        let mut out = String::from(concat!(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n",
+            "<graphml xmlns=\"http://graphml.graphdrawing.org/xmlns\">\n",
+            "  <key id=\"area\" for=\"node\" attr.name=\"area\" attr.type=\"long\"/>\n",
+            "  <key id=\"centroid_x\" for=\"node\" attr.name=\"centroid_x\" attr.type=\"double\"/>\n",
+            "  <key id=\"centroid_y\" for=\"node\" attr.name=\"centroid_y\" attr.type=\"double\"/>\n",
+            "  <key id=\"mean_color\" for=\"node\" attr.name=\"mean_color\" attr.type=\"string\"/>\n",
+            "  <key id=\"border_length\" for=\"edge\" attr.name=\"border_length\" attr.type=\"long\"/>\n",
+            "  <key id=\"distance\" for=\"edge\" attr.name=\"distance\" attr.type=\"double\"/>\n",
+            "  <graph id=\"regions\" edgedefault=\"undirected\">\n",
+        ));
+        for r in &self.regions {
+            let _ = write!(
+                out,
+                concat!(
+                    "    <node id=\"n{}\">\n",
+                    "      <data key=\"area\">{}</data>\n",
+                    "      <data key=\"centroid_x\">{}</data>\n",
+                    "      <data key=\"centroid_y\">{}</data>\n",
+                    "      <data key=\"mean_color\">{}</data>\n",
+                    "    </node>\n",
+                ),
+                r.id,
+                r.pixel_count,
+                r.centroid.x,
+                r.centroid.y,
+                mean_color_as_list(r).join(","),
+            );
+        }
+        for e in &self.edges {
+            // a missing data element is the graphml way to leave a value undefined
+            let distance = if e.distance.is_finite() {
+                format!("      <data key=\"distance\">{}</data>\n", e.distance)
+            } else {
+                String::new()
+            };
+            let _ = write!(
+                out,
+                concat!(
+                    "    <edge source=\"n{}\" target=\"n{}\">\n",
+                    "      <data key=\"border_length\">{}</data>\n",
+                    "{}",
+                    "    </edge>\n",
+                ),
+                e.regions[0], e.regions[1], e.border_length, distance,
+            );
+        }
+        out.push_str("  </graph>\n</graphml>\n");
+        return out;
+    }
+
+    fn graph_to_json(&self) -> String {
+        let nodes = self
+            .regions
+            .iter()
+            .map(|r| {
+                format!(
+                    "{{\"id\":{},\"area\":{},\"centroid\":[{},{}],\"mean_color\":[{}]}}",
+                    r.id,
+                    r.pixel_count,
+                    json_number(r.centroid.x),
+                    json_number(r.centroid.y),
+                    mean_color_as_list(r).join(","),
+                )
+            })
+            .collect::<Vec<_>>();
+        let edges = self
+            .edges
+            .iter()
+            .map(|e| {
+                format!(
+                    "{{\"source\":{},\"target\":{},\"border_length\":{},\"distance\":{}}}",
+                    e.regions[0],
+                    e.regions[1],
+                    e.border_length,
+                    json_number(e.distance),
+                )
+            })
+            .collect::<Vec<_>>();
+        return format!("{{\"nodes\":[{}],\"edges\":[{}]}}\n", nodes.join(","), edges.join(","));
+    }
+}
+
+/// the channels of the mean color of a region, in the channel order of the original image
+fn mean_color_as_list(region: &Region) -> Vec<String> {
+    return region.mean_color.0[..3].iter().map(|x| json_number(*x)).collect();
+}
@@ -0,0 +1,199 @@
+use opencv::{
+    core::{compare, Scalar, Vec3b, VecN, Vector, CMP_EQ, CV_16U},
+    imgcodecs::imwrite,
+    imgproc::{cvt_color, COLOR_GRAY2BGR},
+};
+use rand::{rngs::StdRng, SeedableRng};
+use std::path::{Path, PathBuf};
+
+use super::*;
+use crate::evaluation::boundary_mask;
+use crate::logger_trait::ColoredArea;
+
+/// color (BGR) of the boundaries between the regions
+const BOUNDARY_COLOR: [u8; 3] = [0, 0, 255];
+
+/// how the regions are colored in the overlay image
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum OverlayKind {
+    /// every region gets a random color (like in the `ImageLogger`), blended with the image.
+    /// The colors are generated from the seed of the result
+    #[default]
+    FalseColor,
+    /// every region is filled with its mean color
+    MeanColor,
+}
+
+/// writes the outputs of a segmentation in a directory, each of them can be enabled independently:
+/// - `labels.png`: 16 bit image with the id of the region of each pixel
+/// - `overlay.png`: the regions colored as specified by the `OverlayKind`
+/// - `boundaries.png`: the borders between the regions drawn on the original image
+/// - `masks/region_<id>.png`: one mask for each region
+pub struct ResultWriter {
+    output_dir: PathBuf,
+    labels: bool,
+    overlay: Option<OverlayKind>,
+    boundaries: bool,
+    masks: bool,
+}
+
+impl ResultWriter {
+    /// by default the labels, the false color overlay and the boundaries are written
+    pub fn new(output_dir: impl Into<PathBuf>) -> Self {
+        return Self {
+            output_dir: output_dir.into(),
+            labels: true,
+            overlay: Some(OverlayKind::FalseColor),
+            boundaries: true,
+            masks: false,
+        };
+    }
+
+    pub fn with_labels(mut self, labels: bool) -> Self {
+        self.labels = labels;
+        return self;
+    }
+
+    /// `None` disables the overlay
+    pub fn with_overlay(mut self, overlay: Option<OverlayKind>) -> Self {
+        self.overlay = overlay;
+        return self;
+    }
+
+    pub fn with_boundaries(mut self, boundaries: bool) -> Self {
+        self.boundaries = boundaries;
+        return self;
+    }
+
+    pub fn with_masks(mut self, masks: bool) -> Self {
+        self.masks = masks;
+        return self;
+    }
+
+    pub fn write(&self, result: &SegmentationResult, image: &ImageContainer) -> Result<()> {
+        std::fs::create_dir_all(&self.output_dir)
+            .with_context(|| format!("unable to create the directory {}", self.output_dir.display()))?;
+        let original_image = image.get_original_image();
+
+        if self.labels {
+            write_image(&self.output_dir.join("labels.png"), &result.get_labels_16_bit()?)?;
+        }
+        if let Some(kind) = self.overlay {
+            write_image(
+                &self.output_dir.join("overlay.png"),
+                &result.get_overlay(original_image, kind)?,
+            )?;
+        }
+        if self.boundaries {
+            write_image(
+                &self.output_dir.join("boundaries.png"),
+                &result.get_boundaries(original_image)?,
+            )?;
+        }
+        if self.masks {
+            let masks_dir = self.output_dir.join("masks");
+            std::fs::create_dir_all(&masks_dir)
+                .with_context(|| format!("unable to create the directory {}", masks_dir.display()))?;
+            for region in &result.regions {
+                write_image(
+                    &masks_dir.join(format!("region_{}.png", region.id)),
+                    &result.get_region_mask(region.id)?,
+                )?;
+            }
+        }
+        return Ok(());
+    }
+}
+
+impl SegmentationResult {
+    /// the label image converted to `CV_16U`, so that it can be saved as a png
+    pub fn get_labels_16_bit(&self) -> Result<Mat> {
+        if self.regions.len() > u16::MAX as usize + 1 {
+            return Err(anyhow!(
+                "{} regions can't be represented in a 16 bit image",
+                self.regions.len()
+            ));
+        }
+        let mut labels = Mat::default();
+        self.labels.convert_to(&mut labels, CV_16U, 1., 0.)?;
+        return Ok(labels);
+    }
+
+    /// the image with every region colored as specified by the `OverlayKind`
+    pub fn get_overlay(&self, image: &Mat, kind: OverlayKind) -> Result<Mat> {
+        let (colors, alpha) = match kind {
+            OverlayKind::FalseColor => (
+                {
+                    let mut rng = StdRng::seed_from_u64(self.seed);
+                    self.regions
+                        .iter()
+                        .map(|_| ColoredArea::get_random_color(&mut rng))
+                        .collect::<Result<Vec<_>>>()?
+                },
+                0.5,
+            ),
+            OverlayKind::MeanColor => (
+                self.regions
+                    .iter()
+                    .map(|r| match image.channels() {
+                        1 => Scalar::all(r.mean_color[0]),
+                        _ => r.mean_color,
+                    })
+                    .collect(),
+                1.,
+            ),
+        };
+
+        let mut output = to_bgr(image)?;
+        let labels = self.labels.data_typed::<i32>()?;
+        for (pixel, label) in output.data_typed_mut::<Vec3b>()?.iter_mut().zip(labels) {
+            let color = colors
+                .get(*label as usize)
+                .ok_or(anyhow!("pixel with invalid label {label}"))?;
+            for c in 0..3 {
+                pixel[c] = (pixel[c] as f64 * (1. - alpha) + color[c] * alpha).round() as u8;
+            }
+        }
+        return Ok(output);
+    }
+
+    /// the image with the pixels on the border between two regions highlighted
+    pub fn get_boundaries(&self, image: &Mat) -> Result<Mat> {
+        let mut output = to_bgr(image)?;
+        let width = self.labels.cols() as usize;
+        let labels = self.labels.data_typed::<i32>()?;
+        let pixels = output.data_typed_mut::<Vec3b>()?;
+
+        for (pixel, is_boundary) in pixels.iter_mut().zip(boundary_mask(labels, width)) {
+            if is_boundary {
+                *pixel = VecN(BOUNDARY_COLOR);
+            }
+        }
+        return Ok(output);
+    }
+
+    /// `CV_8U` mask with 255 on the pixels of the region
+    pub fn get_region_mask(&self, region_id: usize) -> Result<Mat> {
+        let mut mask = Mat::default();
+        compare(&self.labels, &Scalar::all(region_id as f64), &mut mask, CMP_EQ)?;
+        return Ok(mask);
+    }
+}
+
+fn to_bgr(image: &Mat) -> Result<Mat> {
+    if image.channels() == 1 {
+        let mut bgr = Mat::default();
+        cvt_color(image, &mut bgr, COLOR_GRAY2BGR, 0)?;
+        return Ok(bgr);
+    }
+    return Ok(image.try_clone()?);
+}
+
+fn write_image(path: &Path, image: &Mat) -> Result<()> {
+    let written = imwrite(&path.to_string_lossy(), image, &Vector::new())
+        .with_context(|| format!("unable to write {}", path.display()))?;
+    if !written {
+        return Err(anyhow!("unable to write {}", path.display()));
+    }
+    return Ok(());
+}
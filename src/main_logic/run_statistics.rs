@@ -0,0 +1,33 @@
+use std::time::Duration;
+
+/// timings and counters of a complete run of the split and merge algorithm, useful to tune the
+/// number of workers and the thresholds
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct RunStatistics {
+    /// wall time of the split phase
+    pub split_time: Duration,
+    /// wall time spent computing the statistics and the adjacency of the leafs of the split tree
+    pub initialization_time: Duration,
+    /// wall time of the merge phase, initialization excluded
+    pub merge_time: Duration,
+    /// wall time of the batches of requests evaluated on the worker pool, from the dispatch of the
+    /// requests to the collection of their results (the main thread does nothing else meanwhile)
+    pub pool_time: Duration,
+    /// number of times the splitter has been asked whether to split an area
+    pub splitter_calls: usize,
+    /// number of areas that have been split
+    pub splits: usize,
+    /// number of batches of split requests evaluated on the worker pool
+    pub split_rounds: usize,
+    /// number of times the merger has evaluated (merged or scored) a couple of areas
+    pub merger_calls: usize,
+    /// number of batches of merge requests evaluated on the worker pool
+    /// (`MergeStrategy::BestFirst` has a single batch, the initial scoring)
+    pub merge_rounds: usize,
+    pub accepted_merges: usize,
+    pub rejected_merges: usize,
+    /// highest number of areas that existed at the same time
+    pub peak_regions: usize,
+    /// the run has been stopped by a `CancellationToken`
+    pub cancelled: bool,
+}
@@ -0,0 +1,162 @@
+use opencv::{
+    core::{sum_elems, Point2d, Rect, Scalar, VecN, CV_32S},
+    imgproc::{rectangle, LINE_8},
+};
+use std::collections::{BTreeMap, HashMap};
+
+use super::*;
+
+/// a single region of the final segmentation
+#[derive(Debug, Clone)]
+pub struct Region {
+    /// id of the region, it is also the value used for this region in the label image
+    pub id: usize,
+    pub pixel_count: usize,
+    pub bounding_box: Rect,
+    /// mean position of the pixels of the region
+    pub centroid: Point2d,
+    /// mean color of the region in the original image (before any color space conversion)
+    pub mean_color: Scalar,
+}
+
+/// the result of a complete split and merge run, that can be used directly by downstream code
+/// without having to go trough a logger
+#[derive(Debug)]
+pub struct SegmentationResult {
+    /// `CV_32S` image with the same size as the input, every pixel contains the id of the region
+    /// it belongs to
+    pub labels: Mat,
+    /// one entry for each of the final regions, the index in the vector is the id of the region
+    pub regions: Vec<Region>,
+    /// map from the id of each leaf of the split tree to the id of the region it ended up in
+    pub leaf_to_region: BTreeMap<usize, usize>,
+    /// edges of the region adjacency graph, sorted by the ids of the two regions
+    pub edges: Vec<RegionEdge>,
+    /// the run has been stopped by a `CancellationToken` before the end, so some areas may
+    /// not have been split or merged
+    pub is_partial: bool,
+    /// seed of the run (see `MainLogic::with_seed`), also used for the colors of
+    /// `OverlayKind::FalseColor`
+    pub seed: u64,
+}
+
+impl SegmentationResult {
+    /// the pixels of each region are taken from the rectangles of its area (see `MergeState`),
+    /// the leafs are only used to map them to their region
+    pub(super) fn new(
+        image: &ImageContainer,
+        areas: &HashMap<usize, Area>,
+        leafs: &[usize],
+        disjoint_sets: &DisjointSets,
+        adjacent_leafs: &[([usize; 2], usize)],
+        distance: impl Fn(usize, usize) -> Result<f64>,
+    ) -> Result<Self> {
+        // the roots are sorted, so the region ids follow the ids of the areas
+        let roots = disjoint_sets.get_root_items().copied().collect::<Vec<_>>();
+        let root_to_region = roots
+            .iter()
+            .enumerate()
+            .map(|(region_id, root_id)| (*root_id, region_id))
+            .collect::<HashMap<_, _>>();
+
+        let mut labels = Mat::new_rows_cols_with_default(
+            image.height,
+            image.width,
+            CV_32S,
+            Scalar::all(-1.),
+        )?;
+
+        let mut sums = vec![[0f64; 4]; roots.len()];
+        let mut position_sums = vec![[0f64; 2]; roots.len()];
+        let mut regions = (0..roots.len())
+            .map(|id| Region {
+                id,
+                pixel_count: 0,
+                bounding_box: Rect::default(),
+                centroid: Point2d::default(),
+                mean_color: Scalar::default(),
+            })
+            .collect::<Vec<_>>();
+
+        for (region_id, root_id) in roots.iter().enumerate() {
+            let area = areas
+                .get(root_id)
+                .ok_or(anyhow!("area of the root item {root_id} not found"))?;
+            let region = &mut regions[region_id];
+            region.bounding_box = area.marker.bounding_box();
+            region.pixel_count = area.marker.pixel_count();
+
+            for rect in area.marker.get_rects() {
+                rectangle(
+                    &mut labels,
+                    *rect,
+                    Scalar::all(region_id as f64),
+                    -1,
+                    LINE_8,
+                    0,
+                )?;
+
+                // sum of the coordinates of the pixels of the rectangle
+                let pixel_count = (rect.width * rect.height) as f64;
+                position_sums[region_id][0] += pixel_count * (rect.x as f64 + (rect.width - 1) as f64 / 2.);
+                position_sums[region_id][1] += pixel_count * (rect.y as f64 + (rect.height - 1) as f64 / 2.);
+
+                let rect_sum = sum_elems(&Mat::roi(image.get_original_image(), *rect)?)?;
+                for (acc, value) in sums[region_id].iter_mut().zip(rect_sum.0) {
+                    *acc += value;
+                }
+            }
+        }
+
+        let mut leaf_to_region = BTreeMap::new();
+        for leaf_id in leafs {
+            let root_id = disjoint_sets
+                .get_father_of(*leaf_id)
+                .ok_or(anyhow!("leaf {leaf_id} is not part of the disjoint sets"))?;
+            let region_id = *root_to_region
+                .get(&root_id)
+                .ok_or(anyhow!("the father of leaf {leaf_id} is not a root item"))?;
+            leaf_to_region.insert(*leaf_id, region_id);
+        }
+
+        for ((region, sum), [x, y]) in regions.iter_mut().zip(sums).zip(position_sums) {
+            if region.pixel_count != 0 {
+                let n = region.pixel_count as f64;
+                region.mean_color = VecN(sum.map(|x| x / n));
+                region.centroid = Point2d::new(x / n, y / n);
+            }
+        }
+
+        // the border between two regions is the sum of the borders between their leafs
+        let mut border_lengths = BTreeMap::new();
+        for ([a, b], border_length) in adjacent_leafs {
+            let region_a = leaf_to_region[a];
+            let region_b = leaf_to_region[b];
+            if region_a != region_b {
+                let key = [region_a.min(region_b), region_a.max(region_b)];
+                *border_lengths.entry(key).or_insert(0) += border_length;
+            }
+        }
+        let edges = border_lengths
+            .into_iter()
+            .map(|([a, b], border_length)| {
+                let distance = distance(roots[a], roots[b])
+                    .with_context(|| format!("unable to compute the distance of regions {a} and {b}"))?;
+                Ok(RegionEdge {
+                    regions: [a, b],
+                    border_length,
+                    distance,
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        return Ok(Self {
+            labels,
+            regions,
+            leaf_to_region,
+            edges,
+            is_partial: false,
+            seed: DEFAULT_SEED,
+        });
+    }
+}
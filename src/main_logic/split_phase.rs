@@ -1,8 +1,15 @@
 use super::*;
+use std::time::Instant;
 
 
 impl<'a, S: SplitterTrait, M: MergerTrait, L: LoggerTrait> MainLogic<'a, S, M, L, SplitState> {
-    pub fn new(splitter: S, merger: M, logger: L, image: &'a ImageContainer) -> Self {
+    pub fn new(
+        splitter: S,
+        merger: M,
+        logger: L,
+        image: &'a ImageContainer,
+        pool: &'a WorkerPool,
+    ) -> Self {
         let image_split = image.to_image_container_split();
         let split_tree = vec![SplitTree {
             id: 0,
@@ -10,15 +17,8 @@ impl<'a, S: SplitterTrait, M: MergerTrait, L: LoggerTrait> MainLogic<'a, S, M, L
             childs: None,
         }];
 
-        let (images_to_split_tx, images_to_split_rx) = channel();
-        let (split_result_tx, split_result_rx) = channel();
         let state = SplitState {
             yet_to_split_images: vec![0],
-            images_to_split_tx,
-            images_to_split_rx: Arc::new(Mutex::new(images_to_split_rx)),
-            split_result_tx: Arc::new(Mutex::new(split_result_tx)),
-            split_result_rx,
-            items_in_queue: 0,
         };
 
         return MainLogic {
@@ -26,180 +26,146 @@ impl<'a, S: SplitterTrait, M: MergerTrait, L: LoggerTrait> MainLogic<'a, S, M, L
             merger,
             logger,
             image,
+            pool,
             split_tree,
             state,
+            merge_strategy: MergeStrategy::default(),
+            statistics: RunStatistics::default(),
+            progress_callback: None,
+            cancellation_token: CancellationToken::default(),
+            seed: DEFAULT_SEED,
         };
     }
 
-    pub fn execute_split(mut self, num_of_workers: usize) -> MainLogic<'a, S, M, L, MergeState> {
-        info!("Start thread spawning");
-        let join_handlers = self.launch_threads(num_of_workers);
-        info!("Done with thread spawning");
-
+    pub fn execute_split(mut self, num_of_workers: usize) -> Result<MainLogic<'a, S, M, L, MergeState>> {
         info!("Starting execute split loop");
-        loop {
-            // send all necessary split requests
-            info!("main thread: start sending");
-            while self.send_split_request() {
-                // info! {"main thread send one piece"}
-            }
-            info!("main thread: end sending");
-            // if we no longer have anything to
-            if self.state.items_in_queue == 0 {
-                break;
-            }
-            // elaborate eventual received results
-            info!("main thread: start receive");
-            self.receive_split_result();
-            info!("main thread: end receive");
-        }
-
+        let start = Instant::now();
+        self.split_loop(num_of_workers)
+            .context("split phase has failed")?;
+        self.statistics.split_time = start.elapsed();
         info!("Exited execute split loop");
 
-        drop(self.state.images_to_split_tx);
-        drop(self.state.split_result_rx);
-
-        info!("Start thread join");
-        join_handlers.into_iter().for_each(|x| {
-            let _ = x
-                .join()
-                .expect("one of the threads has exited unsuccessfully");
-        });
-        info!("Done thread join");
+        // the number of areas only grows while splitting, and only decreases while merging
+        self.statistics.peak_regions = self.split_tree.len() - self.statistics.splits;
 
-        return MainLogic {
+        return Ok(MainLogic {
             image: self.image,
+            pool: self.pool,
             splitter: self.splitter,
             merger: self.merger,
             logger: self.logger,
             state: MergeState::default(),
             split_tree: self.split_tree,
-        };
+            merge_strategy: self.merge_strategy,
+            statistics: self.statistics,
+            progress_callback: self.progress_callback,
+            cancellation_token: self.cancellation_token,
+            seed: self.seed,
+        });
     }
 
-    fn launch_threads(&self, num_of_workers: usize) -> Vec<JoinHandle<Result<()>>> {
-        let mut join_handlers = Vec::new();
-        for i in 0..num_of_workers {
-            let rx = self.state.images_to_split_rx.clone();
-            let tx = self.state.split_result_tx.clone();
-            // I am sure that this reference will stay valid for as long as the thread below exist
-            let splitter: &'static S = unsafe { std::mem::transmute(&self.splitter) };
-            join_handlers.push(thread::spawn(move || -> Result<()> {
-                info!("thread {i} started");
-                loop {
-                    // info!("thread {i} rx lock");
-                    let rx_locked = rx.lock().map_err(|_| anyhow!("main tread has fail"))?;
-                    // info!("thread {i} rx locked");
-
-                    let (img, id) = rx_locked.recv()?;
-                    drop(rx_locked);
-                    // info!("thread {i} receive id={id}");
-
-                    let split_result = splitter.split(&img.image);
-                    // info!("thread {i} split result = {:?}", split_result);
-
-                    // info!("thread {i} tx lock");
-                    let tx_lock = tx.lock().map_err(|_| anyhow!("main tread has fail"))?;
-                    // info!("thread {i} tx locked");
-
-                    if let Some((direction, split_at)) = split_result {
-                        tx_lock
-                            .send(Some((direction, split_at, id)))
-                            .expect("send messages should never fail");
-                    } else {
-                        tx_lock.send(None).expect("send messages should never fail");
-                    }
-                    drop(tx_lock);
-                    img.destroy();
-                    // info!("thread {i} successfly processed id={id}");
+    fn split_loop(&mut self, num_of_workers: usize) -> Result<()> {
+        // if we no longer have anything to split we are done
+        while !self.state.yet_to_split_images.is_empty() {
+            let to_split = std::mem::take(&mut self.state.yet_to_split_images);
+
+            info!("main thread: evaluating {} split requests", to_split.len());
+            let start = Instant::now();
+            let split_results = self.evaluate_splits(&to_split, num_of_workers)?;
+            self.statistics.pool_time += start.elapsed();
+
+            // the results of an interrupted round are discarded, the areas stay as they are
+            let split_results = match split_results {
+                Some(split_results) if !self.cancellation_token.is_cancelled() => split_results,
+                _ => {
+                    info!("main thread: split phase cancelled");
+                    self.statistics.cancelled = true;
+                    return Ok(());
                 }
-            }));
-        }
-        return join_handlers;
-    }
-
-    fn send_split_request(&mut self) -> bool {
-        // extract the image that need to be split
-        let to_split_id = self.state.yet_to_split_images.pop();
-        let to_split_id = match to_split_id {
-            Some(e) => e,
-            _ => return false,
-        };
-
-        // info!("main thread send request for id={to_split_id}");
+            };
+            self.statistics.split_rounds += 1;
+            self.statistics.splitter_calls += to_split.len();
 
-        let to_split = &self.split_tree[to_split_id].image;
-        let to_split = unsafe { UnmanagedMat::from_image_container_split(to_split) };
+            info!("main thread: applying split results");
+            self.apply_splits(&to_split, split_results)?;
 
-        // send the image
-        self.state
-            .images_to_split_tx
-            .send((to_split, to_split_id))
-            .expect("there should always be a thread listening");
-        self.state.items_in_queue += 1;
-        return true;
+            self.report_progress(Progress::Split {
+                round: self.statistics.split_rounds,
+                split_areas: self.statistics.splits,
+                pending: self.state.yet_to_split_images.len(),
+            });
+        }
+        Ok(())
     }
 
-    fn receive_split_result(&mut self) {
-        assert_ne!(self.state.items_in_queue,0,"can't receive a message if there are no items in the queue, as doing so would deadlock the program");
-
-        info! {"start listening with {} elements",self.state.items_in_queue};
-
-        for _ in 0..self.state.items_in_queue {
-            let result = self
-                .state
-                .split_result_rx
-                .recv()
-                .expect("there should always be a thread listening");
-            // info! {"got result: {:?}",result};
-
-            self.state.items_in_queue -= 1;
+    /// ask the splitter (on the worker pool) whether each of the areas must be split.
+    /// The workers only borrow the splitter and the split tree, that is not modified until all
+    /// the results are collected.
+    /// `None` is returned if the run has been cancelled while the requests were evaluated
+    fn evaluate_splits(
+        &self,
+        to_split: &[usize],
+        num_of_workers: usize,
+    ) -> Result<Option<Vec<Option<Split>>>> {
+        let splitter = &self.splitter;
+        let split_tree = &self.split_tree;
+        let cancellation_token = &self.cancellation_token;
+
+        let results = self
+            .pool
+            .parallel_map(num_of_workers, to_split, |id| {
+                if cancellation_token.is_cancelled() {
+                    return Ok(None);
+                }
+                splitter
+                    .split(&split_tree[*id].image)
+                    .with_context(|| format!("splitter has failed on area {id}"))
+                    .map(Some)
+            })?
+            .into_iter()
+            .collect::<Result<Vec<_>>>()?;
+        return Ok(results.into_iter().collect());
+    }
 
-            let (direction, split_at, id_splitted) = match result {
+    fn apply_splits(
+        &mut self,
+        to_split: &[usize],
+        split_results: Vec<Option<Split>>,
+    ) -> Result<()> {
+        for (id_splitted, result) in to_split.iter().copied().zip(split_results) {
+            let split = match result {
                 Some(e) => e,
                 _ => continue,
             };
 
-            let id_1 = self.split_tree.len();
-            let id_2 = id_1 + 1;
+            let images = self.split_tree[id_splitted].image.apply_split(split)
+            .with_context(|| format!(
+                "the splitter has returned an invalid split configuraton: {:?}",
+                split
+            ))?;
 
-            let [img_1, img_2] = self.split_tree[id_splitted].image.split(direction, split_at)
-            .expect( &format!(
-                "the splitter has givven returned an invalid split configuraton: direction={:?} split_at={} ",
-                direction,
-                split_at
-            ));
-
-            // let mut img1_big = Mat::default();
-            // resize(&img_1.image, &mut img1_big, Size::new(img_1.image.cols()*8, img_1.image.rows()*8), 0., 0., INTER_LINEAR).unwrap();
-            // let mut img2_big = Mat::default();
-            // resize(&img_2.image, &mut img2_big, Size::new(img_2.image.cols()*8, img_2.image.rows()*8), 0., 0., INTER_LINEAR).unwrap();
-            // imshow("img1", &img1_big).unwrap();
-            // imshow("img2", &img2_big).unwrap();
+            // the new areas get consecutive ids
+            let first_id = self.split_tree.len();
+            self.statistics.splits += 1;
+            let ids = (first_id..first_id + images.len()).collect::<Vec<_>>();
 
             self.logger
                 .log_split(
                     id_splitted,
-                    [
-                        Area::new_from_split(id_1, &img_1),
-                        Area::new_from_split(id_2, &img_2),
-                    ],
+                    ids.iter()
+                        .zip(&images)
+                        .map(|(id, image)| Area::new_from_split(*id, image))
+                        .collect(),
                 )
-                .expect("logger trait has failed");
-
-            unsafe {
-                // the value is already borrowed immutably  by the split, therefore pushing should not
-                // be safe (as we might move some elements around) however in this case the lifetime of
-                // the  split tree is only referencing the c++ heap allocated object, and not any data
-                // inside the vector, therefore we can do this
-                let split_tree_ptr = &self.split_tree as *const Vec<_> as *mut Vec<SplitTree<'_>>;
-                (*split_tree_ptr)[id_splitted].childs = Some([id_1, id_2]);
-                (*split_tree_ptr).push(SplitTree::new(id_1, img_1));
-                (*split_tree_ptr).push(SplitTree::new(id_2, img_2));
-            }
+                .context("logger trait has failed")?;
 
-            self.state.yet_to_split_images.push(id_1);
-            self.state.yet_to_split_images.push(id_2);
+            // the new splits only borrow the image container, so the tree can be freely modified
+            self.split_tree[id_splitted].childs = Some(ids.clone());
+            for (id, image) in ids.into_iter().zip(images) {
+                self.split_tree.push(SplitTree::new(id, image));
+                self.state.yet_to_split_images.push(id);
+            }
         }
+        Ok(())
     }
 }
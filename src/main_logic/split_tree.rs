@@ -1,9 +1,10 @@
 use super::*;
+use opencv::core::Rect;
 
 pub struct SplitTree<'a> {
     pub id: usize,
     pub image: ImageContainerSplit<'a>,
-    pub childs: Option<[usize; 2]>,
+    pub childs: Option<Vec<usize>>,
 }
 
 impl<'a> SplitTree<'a> {
@@ -16,11 +17,129 @@ impl<'a> SplitTree<'a> {
     } 
 
     pub fn collect_leafs(&'a self, others: &'a [SplitTree<'a>], accumulator: &mut Vec<(usize,&ImageContainerSplit<'a>)>){
-        if let Some([a,b]) = self.childs{
-            others[a].collect_leafs(others, accumulator);
-            others[b].collect_leafs(others, accumulator);
+        if let Some(childs) = &self.childs{
+            for child in childs {
+                others[*child].collect_leafs(others, accumulator);
+            }
         }else{
             accumulator.push((self.id, &self.image));
         }
     }
+
+    /// collect all the couples of adjacent leafs, together with the length of the border they share.
+    /// Two leafs can only touch along the cuts of their lowest common ancestor, so for each couple
+    /// of childs that share a cut, only the leafs that lie on the two sides of the cut are
+    /// compared, with a sweep along the cut
+    pub fn collect_adjacent_leafs(&self, others: &[SplitTree<'a>], accumulator: &mut Vec<([usize; 2], usize)>) {
+        let childs = match &self.childs {
+            Some(childs) => childs,
+            None => return,
+        };
+        for child in childs {
+            others[*child].collect_adjacent_leafs(others, accumulator);
+        }
+
+        for (i, a) in childs.iter().enumerate() {
+            for b in &childs[i + 1..] {
+                let (a, b) = (&others[*a], &others[*b]);
+                let (rect_a, rect_b) = (a.image.get_rect(), b.image.get_rect());
+
+                // childs that only touch at a corner have no leafs in common along the cut,
+                // so they are discarded by the sweep
+                let (side_a, side_b) = if rect_a.x + rect_a.width == rect_b.x {
+                    (Side::Right, Side::Left)
+                } else if rect_b.x + rect_b.width == rect_a.x {
+                    (Side::Left, Side::Right)
+                } else if rect_a.y + rect_a.height == rect_b.y {
+                    (Side::Bottom, Side::Top)
+                } else if rect_b.y + rect_b.height == rect_a.y {
+                    (Side::Top, Side::Bottom)
+                } else {
+                    continue;
+                };
+
+                let mut border_a = Vec::new();
+                let mut border_b = Vec::new();
+                a.collect_leafs_on_side(others, side_a, &mut border_a);
+                b.collect_leafs_on_side(others, side_b, &mut border_b);
+                sweep_border(border_a, border_b, accumulator);
+            }
+        }
+    }
+
+    /// collect the leafs that touch one side of this node, together with the interval of the side
+    /// they cover
+    fn collect_leafs_on_side(&self, others: &[SplitTree<'a>], side: Side, accumulator: &mut Vec<(usize, i32, i32)>) {
+        let rect = self.image.get_rect();
+        let childs = match &self.childs {
+            Some(childs) => childs,
+            None => {
+                let (start, end) = side.interval(rect);
+                accumulator.push((self.id, start, end));
+                return;
+            }
+        };
+        for child in childs {
+            let child = &others[*child];
+            if side.edge(child.image.get_rect()) == side.edge(rect) {
+                child.collect_leafs_on_side(others, side, accumulator);
+            }
+        }
+    }
+}
+
+/// find the leafs on the two sides of a cut whose intervals overlap, the leafs on each side cover
+/// disjoint intervals of the cut
+fn sweep_border(
+    mut border_a: Vec<(usize, i32, i32)>,
+    mut border_b: Vec<(usize, i32, i32)>,
+    accumulator: &mut Vec<([usize; 2], usize)>,
+) {
+    border_a.sort_by_key(|(_, start, _)| *start);
+    border_b.sort_by_key(|(_, start, _)| *start);
+
+    let (mut i, mut j) = (0, 0);
+    while i < border_a.len() && j < border_b.len() {
+        let (id_a, start_a, end_a) = border_a[i];
+        let (id_b, start_b, end_b) = border_b[j];
+
+        let overlap = end_a.min(end_b) - start_a.max(start_b);
+        if overlap > 0 {
+            accumulator.push(([id_a, id_b], overlap as usize));
+        }
+
+        if end_a < end_b {
+            i += 1;
+        } else {
+            j += 1;
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Side {
+    Left,
+    Right,
+    Top,
+    Bottom,
+}
+
+impl Side {
+    /// coordinate of this side of the rectangle
+    fn edge(&self, rect: Rect) -> i32 {
+        return match self {
+            Side::Left => rect.x,
+            Side::Right => rect.x + rect.width,
+            Side::Top => rect.y,
+            Side::Bottom => rect.y + rect.height,
+        };
+    }
+
+    /// interval covered by this side of the rectangle
+    fn interval(&self, rect: Rect) -> (i32, i32) {
+        return match self {
+            Side::Left | Side::Right => (rect.y, rect.y + rect.height),
+            Side::Top | Side::Bottom => (rect.x, rect.x + rect.width),
+        };
+    }
 }
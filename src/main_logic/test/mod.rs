@@ -11,9 +11,10 @@ fn test_split_simple() {
     let merger = merger_traits::BlindMerger::new();
     let logger = logger_traits::NullLogger::new();
 
-    let logic = MainLogic::new(splitter, merger, logger, &i);
+    let pool = WorkerPool::new(4).expect("worker pool creation should not fail");
+    let logic = MainLogic::new(splitter, merger, logger, &i, &pool);
 
-    let _ = logic.execute_split(4);
+    let _ = logic.execute_split(4).expect("split phase should not fail");
 }
 
 #[test]
@@ -23,11 +24,12 @@ fn test_split_image_logger() {
 
     let splitter = splitter_traits::BlindSplitter::new(50);
     let merger = merger_traits::BlindMerger::new();
-    let logger = logger_traits::ImageLogger::new(i.image.clone());
+    let logger = logger_traits::ImageLogger::new(i.image.clone()).expect("logger creation should not fail");
 
-    let logic = MainLogic::new(splitter, merger, logger, &i);
+    let pool = WorkerPool::new(4).expect("worker pool creation should not fail");
+    let logic = MainLogic::new(splitter, merger, logger, &i, &pool);
 
-    let _ = logic.execute_split(4);
+    let _ = logic.execute_split(4).expect("split phase should not fail");
 }
 
 #[test]
@@ -39,13 +41,13 @@ fn test_correct_splitting() {
         image: Mat,
     }
     impl LoggerTrait for TestLogger {
-        fn log_split(&mut self, _area_to_split_id: usize, splits: [Area; 2]) -> Result<()> {
-            let [mut a1, mut a2] = splits;
-            let mask_a1 = a1.get_mat_area(&self.image);
-            let mask_a2 = a2.get_mat_area(&self.image);
+        fn log_split(&mut self, _area_to_split_id: usize, splits: Vec<Area>) -> Result<()> {
+            let [a1, a2] = <[Area; 2]>::try_from(splits).expect("blind splitter only does binary splits");
+            let mask_a1 = a1.to_mask(&self.image)?;
+            let mask_a2 = a2.to_mask(&self.image)?;
 
             let result =
-                check_mask_correct_split(mask_a1, mask_a2).expect("check correct split has failed");
+                check_mask_correct_split(&mask_a1, &mask_a2).expect("check correct split has failed");
 
             assert!(result, "bad split detected!");
 
@@ -67,7 +69,668 @@ fn test_correct_splitting() {
         image: i.image.clone(),
     };
 
-    let logic = MainLogic::new(splitter, merger, logger, &i);
+    let pool = WorkerPool::new(4).expect("worker pool creation should not fail");
+    let logic = MainLogic::new(splitter, merger, logger, &i, &pool);
 
-    let _ = logic.execute_split(4);
+    let _ = logic.execute_split(4).expect("split phase should not fail");
+}
+
+#[test]
+fn test_segmentation_result() {
+    let i = ImageContainer::new_from_file_color("./test_images/200x100_split.jpg")
+        .expect("test file must be present");
+
+    let splitter = splitter_traits::BlindSplitter::new(50);
+    let merger = merger_traits::BlindMerger::new();
+    let logger = logger_traits::NullLogger::new();
+
+    let pool = WorkerPool::new(4).expect("worker pool creation should not fail");
+    let logic = MainLogic::new(splitter, merger, logger, &i, &pool);
+    let logic = logic.execute_split(4).expect("split phase should not fail");
+    let logic = logic.execute_merge(4).expect("merge phase should not fail");
+    let result = logic.get_result();
+
+    // the blind merger merges everything, so only one region should be left
+    assert_eq!(result.regions.len(), 1);
+    assert_eq!(result.regions[0].pixel_count, (i.width * i.height) as usize);
+    assert_eq!(result.regions[0].bounding_box.width, i.width);
+    assert_eq!(result.regions[0].bounding_box.height, i.height);
+    assert!(result.leaf_to_region.len() > 1);
+    assert!(result.leaf_to_region.values().all(|x| *x == 0));
+
+    assert_eq!(result.labels.typ(), opencv::core::CV_32S);
+    assert_eq!(result.labels.rows(), i.height);
+    assert_eq!(result.labels.cols(), i.width);
+    let mut min_label = 0.;
+    let mut max_label = 0.;
+    opencv::core::min_max_loc(
+        &result.labels,
+        Some(&mut min_label),
+        Some(&mut max_label),
+        None,
+        None,
+        &opencv::core::no_array(),
+    )
+    .expect("min max loc should not fail");
+    assert_eq!(min_label, 0.);
+    assert_eq!(max_label, 0.);
+}
+
+#[test]
+fn test_worker_errors_are_propagated() {
+    let i = ImageContainer::new_from_file_color("./test_images/200x100_split.jpg")
+        .expect("test file must be present");
+
+    struct FailingSplitter {}
+    impl SplitterTrait for FailingSplitter {
+        fn split(&self, _image: &ImageContainerSplit<'_>) -> Result<Option<Split>> {
+            Err(anyhow!("failing splitter"))
+        }
+    }
+
+    struct FailingMerger {}
+    impl MergerTrait for FailingMerger {
+        fn score(&self, _stats_a: &RegionStatistics, _stats_b: &RegionStatistics) -> Result<f64> {
+            Err(anyhow!("failing merger"))
+        }
+        fn threshold(&self) -> f64 {
+            0.
+        }
+    }
+
+    let pool = WorkerPool::new(4).expect("worker pool creation should not fail");
+    let logic = MainLogic::new(
+        FailingSplitter {},
+        merger_traits::BlindMerger::new(),
+        logger_traits::NullLogger::new(),
+        &i,
+        &pool,
+    );
+    let _ = logic.execute_split(4).err().expect("split phase should fail");
+
+    let logic = MainLogic::new(
+        splitter_traits::BlindSplitter::new(50),
+        FailingMerger {},
+        logger_traits::NullLogger::new(),
+        &i,
+        &pool,
+    );
+    let logic = logic.execute_split(4).expect("split phase should not fail");
+    let _ = logic.execute_merge(4).err().expect("merge phase should fail");
+}
+
+#[test]
+fn test_worker_pool_reuse() {
+    let i = ImageContainer::new_from_file_color("./test_images/200x100_split.jpg")
+        .expect("test file must be present");
+
+    let pool = WorkerPool::new(4).expect("worker pool creation should not fail");
+
+    let mut num_regions = Vec::new();
+    for _ in 0..3 {
+        let splitter = splitter_traits::StdSplitter::new(10, 30.);
+        let merger = merger_traits::StdMerger::new(40.);
+        let logger = logger_traits::NullLogger::new();
+
+        let logic = MainLogic::new(splitter, merger, logger, &i, &pool);
+        let logic = logic.execute_split(4).expect("split phase should not fail");
+        let logic = logic.execute_merge(2).expect("merge phase should not fail");
+        num_regions.push(logic.get_result().regions.len());
+    }
+
+    assert_eq!(pool.num_of_workers(), 4);
+    assert!(num_regions.iter().all(|x| *x > 0));
+}
+
+#[test]
+fn test_adjacency_from_split_tree() {
+    let i = ImageContainer::new_from_file_color("./test_images/200x100_split.jpg")
+        .expect("test file must be present");
+
+    let splitters: Vec<Box<dyn SplitterTrait>> = vec![
+        Box::new(splitter_traits::HeuristicAsymmetricSplitter::new(
+            splitter_traits::StdSplitter::new(3, 10.),
+        )),
+        Box::new(splitter_traits::QuadSplitter::new(
+            splitter_traits::StdSplitter::new(3, 10.),
+        )),
+    ];
+
+    let pool = WorkerPool::new(4).expect("worker pool creation should not fail");
+    for splitter in splitters {
+        let merger = merger_traits::BlindMerger::new();
+        let logger = logger_traits::NullLogger::new();
+
+        let logic = MainLogic::new(splitter, merger, logger, &i, &pool);
+        let logic = logic.execute_split(4).expect("split phase should not fail");
+
+        let mut adjacent_leafs = Vec::new();
+        logic.split_tree[0].collect_adjacent_leafs(&logic.split_tree, &mut adjacent_leafs);
+        let adjacent_leafs = adjacent_leafs
+            .into_iter()
+            .map(|([a, b], border_length)| ([a.min(b), a.max(b)], border_length))
+            .collect::<std::collections::HashMap<_, _>>();
+
+        // compare against the brute force check over all the couples of leafs
+        let mut leafs = Vec::new();
+        logic.split_tree[0].collect_leafs(&logic.split_tree, &mut leafs);
+        let mut num_neighbors = 0;
+        for (a_id, a) in &leafs {
+            for (b_id, b) in &leafs {
+                if a_id >= b_id || !ImageContainerSplit::are_neighbors(a, b) {
+                    continue;
+                }
+                num_neighbors += 1;
+
+                let overlap_x = (a.x_start + a.width).min(b.x_start + b.width) - a.x_start.max(b.x_start);
+                let overlap_y = (a.y_start + a.height).min(b.y_start + b.height) - a.y_start.max(b.y_start);
+                let border_length = overlap_x.max(overlap_y) as usize;
+
+                assert_eq!(adjacent_leafs.get(&[*a_id, *b_id]), Some(&border_length));
+            }
+        }
+        assert_eq!(adjacent_leafs.len(), num_neighbors);
+    }
+}
+
+#[test]
+fn test_best_first_merge_is_deterministic() {
+    let i = ImageContainer::new_from_file_color("./test_images/200x100_split.jpg")
+        .expect("test file must be present");
+
+    let pool = WorkerPool::new(4).expect("worker pool creation should not fail");
+
+    let mut results = Vec::new();
+    for num_of_workers in [1, 4] {
+        let splitter = splitter_traits::StdSplitter::new(10, 30.);
+        let merger = merger_traits::StdMerger::new(40.);
+        let logger = logger_traits::NullLogger::new();
+
+        let logic = MainLogic::new(splitter, merger, logger, &i, &pool)
+            .with_merge_strategy(MergeStrategy::BestFirst);
+        let logic = logic.execute_split(num_of_workers).expect("split phase should not fail");
+        let logic = logic.execute_merge(num_of_workers).expect("merge phase should not fail");
+        results.push(logic.into_result());
+    }
+
+    assert!(!results[0].regions.is_empty());
+    assert_eq!(results[0].regions.len(), results[1].regions.len());
+    assert_eq!(
+        results[0].labels.data_typed::<i32>().expect("labels must be CV_32S"),
+        results[1].labels.data_typed::<i32>().expect("labels must be CV_32S"),
+    );
+}
+
+#[test]
+fn test_texture_splitter_and_merger() {
+    let i = ImageContainer::new_from_file_color("./test_images/200x100_split.jpg")
+        .expect("test file must be present");
+
+    let splitter = splitter_traits::TextureSplitter::new(5, 0.1, 50.);
+    let merger = merger_traits::TextureMerger::new(0.1, 70.);
+    let logger = logger_traits::NullLogger::new();
+
+    let pool = WorkerPool::new(4).expect("worker pool creation should not fail");
+    let logic = MainLogic::new(splitter, merger, logger, &i, &pool);
+    let logic = logic.execute_split(4).expect("split phase should not fail");
+    let logic = logic.execute_merge(4).expect("merge phase should not fail");
+
+    assert!(!logic.get_result().regions.is_empty());
+}
+
+#[test]
+fn test_region_adjacency_graph() {
+    let i = ImageContainer::new_from_file_color("./test_images/200x100_split.jpg")
+        .expect("test file must be present");
+
+    let splitter = splitter_traits::StdSplitter::new(10, 30.);
+    let merger = merger_traits::StdMerger::new(40.);
+    let logger = logger_traits::NullLogger::new();
+
+    let pool = WorkerPool::new(4).expect("worker pool creation should not fail");
+    let logic = MainLogic::new(splitter, merger, logger, &i, &pool);
+    let logic = logic.execute_split(4).expect("split phase should not fail");
+    let logic = logic.execute_merge(4).expect("merge phase should not fail");
+    let mut result = logic.into_result();
+
+    assert!(!result.regions.is_empty());
+    assert_eq!(result.edges.is_empty(), result.regions.len() == 1);
+    for edge in &result.edges {
+        let [a, b] = edge.regions;
+        assert!(a < b && b < result.regions.len());
+        assert!(edge.border_length > 0);
+    }
+    for region in &result.regions {
+        assert!(region.bounding_box.contains(opencv::core::Point::new(
+            region.centroid.x.floor() as i32,
+            region.centroid.y.floor() as i32,
+        )));
+    }
+
+    let dot = result.export_graph(GraphFormat::Dot);
+    assert_eq!(dot.matches("--").count(), result.edges.len());
+    let graphml = result.export_graph(GraphFormat::GraphMl);
+    assert_eq!(graphml.matches("<node ").count(), result.regions.len());
+    let json = result.export_graph(GraphFormat::Json);
+    assert_eq!(json.matches("\"border_length\"").count(), result.edges.len());
+
+    // non finite distances are left undefined in every format
+    let edge = result.edges.first_mut().expect("the test image has more than one region");
+    edge.distance = f64::INFINITY;
+    for format in [GraphFormat::Dot, GraphFormat::GraphMl, GraphFormat::Json] {
+        assert!(!result.export_graph(format).contains("inf"));
+    }
+}
+
+#[test]
+fn test_disjoint_sets_neighbors() {
+    let mut sets = super::DisjointSets::default();
+    for id in 0..4 {
+        sets.add_item(id).expect("add item should not fail");
+    }
+    for (a, b, border_length) in [(0, 1, 1), (1, 2, 2), (2, 3, 3), (0, 2, 4)] {
+        sets.set_as_neighbors(a, b, border_length)
+            .expect("set as neighbors should not fail");
+    }
+    assert_eq!(sets.count_neighbor_pairs(), 4);
+
+    // the neighbors of the new items are resolved to roots, with the borders summed
+    sets.create_new(4, [0, 1]).expect("create new should not fail");
+    assert_eq!(sets.get_border_length(4, 2), Some(6));
+    sets.create_new(5, [2, 3]).expect("create new should not fail");
+    assert_eq!(sets.get_border_length(5, 4), Some(6));
+    assert_eq!(sets.get_root_neighbors(4).expect("neighbors should not fail"), vec![5]);
+    sets.clear_data().expect("clear data should not fail");
+    assert_eq!(sets.count_neighbor_pairs(), 1);
+
+    // 4 still has the key of the leaf 2, that now belongs to 5
+    sets.mark_as_non_neighbors(4, 5).expect("mark as non neighbors should not fail");
+    assert!(sets.get_root_neighbors(4).expect("neighbors should not fail").is_empty());
+    assert!(sets.get_root_neighbors(5).expect("neighbors should not fail").is_empty());
+    assert_eq!(sets.count_neighbor_pairs(), 0);
+
+    // long chains of merges must not overflow the stack when resolving the roots
+    let mut sets = super::DisjointSets::default();
+    let num_of_items = 100_000;
+    for id in 0..num_of_items {
+        sets.add_item(id).expect("add item should not fail");
+    }
+    let mut root = 0;
+    for id in 1..num_of_items {
+        let new_id = num_of_items + id;
+        sets.create_new(new_id, [root, id]).expect("create new should not fail");
+        root = new_id;
+    }
+    assert_eq!(sets.get_father_of(0), Some(root));
+}
+
+#[test]
+fn test_result_outputs() {
+    let i = ImageContainer::new_from_file_color("./test_images/200x100_split.jpg")
+        .expect("test file must be present");
+
+    let splitter = splitter_traits::StdSplitter::new(10, 30.);
+    let merger = merger_traits::StdMerger::new(40.);
+    let logger = logger_traits::NullLogger::new();
+
+    let pool = WorkerPool::new(4).expect("worker pool creation should not fail");
+    let logic = MainLogic::new(splitter, merger, logger, &i, &pool);
+    let logic = logic.execute_split(4).expect("split phase should not fail");
+    let logic = logic.execute_merge(4).expect("merge phase should not fail");
+    let result = logic.get_result();
+
+    let labels = result.get_labels_16_bit().expect("conversion should not fail");
+    assert_eq!(labels.typ(), opencv::core::CV_16U);
+
+    let overlay = result
+        .get_overlay(i.get_original_image(), OverlayKind::MeanColor)
+        .expect("overlay should not fail");
+    assert_eq!(overlay.cols(), i.width);
+    assert_eq!(overlay.rows(), i.height);
+
+    let mut total_pixels = 0;
+    for region in &result.regions {
+        let mask = result.get_region_mask(region.id).expect("mask should not fail");
+        let pixels = opencv::core::count_non_zero(&mask).expect("count should not fail");
+        assert_eq!(pixels as usize, region.pixel_count);
+        total_pixels += region.pixel_count;
+    }
+    assert_eq!(total_pixels, (i.width * i.height) as usize);
+
+    let output_dir = std::env::temp_dir().join("asymmetric_split_and_merge_result_outputs");
+    ResultWriter::new(&output_dir)
+        .with_masks(true)
+        .write(result, &i)
+        .expect("writing the outputs should not fail");
+    for file in ["labels.png", "overlay.png", "boundaries.png", "masks/region_0.png"] {
+        assert!(output_dir.join(file).exists(), "{file} has not been written");
+    }
+    let _ = std::fs::remove_dir_all(&output_dir);
+}
+
+/// twice the signed area of a ring
+fn ring_area(ring: &[opencv::core::Point]) -> i64 {
+    return ring
+        .iter()
+        .zip(ring.iter().cycle().skip(1))
+        .map(|(a, b)| a.x as i64 * b.y as i64 - b.x as i64 * a.y as i64)
+        .sum();
+}
+
+#[test]
+fn test_polygon_export() {
+    let i = ImageContainer::new_from_file_color("./test_images/200x100_split.jpg")
+        .expect("test file must be present");
+
+    let splitter = splitter_traits::StdSplitter::new(10, 30.);
+    let merger = merger_traits::StdMerger::new(40.);
+    let logger = logger_traits::NullLogger::new();
+
+    let pool = WorkerPool::new(4).expect("worker pool creation should not fail");
+    let logic = MainLogic::new(splitter, merger, logger, &i, &pool);
+    let logic = logic.execute_split(4).expect("split phase should not fail");
+    let logic = logic.execute_merge(4).expect("merge phase should not fail");
+    let result = logic.get_result();
+
+    for region in &result.regions {
+        let polygons = result
+            .get_region_polygons(region.id, 0.)
+            .expect("vectorization should not fail");
+        assert!(!polygons.is_empty());
+
+        // the rings follow the edges of the pixels, so they enclose exactly the pixels of the region
+        let bounding_box = region.bounding_box;
+        let mut area = 0;
+        for polygon in &polygons {
+            area += ring_area(&polygon.exterior);
+            area += polygon.holes.iter().map(|h| ring_area(h)).sum::<i64>();
+            for point in polygon.exterior.iter().chain(polygon.holes.iter().flatten()) {
+                assert!(point.x >= bounding_box.x && point.x <= bounding_box.x + bounding_box.width);
+                assert!(point.y >= bounding_box.y && point.y <= bounding_box.y + bounding_box.height);
+            }
+        }
+        assert_eq!(area, 2 * region.pixel_count as i64);
+
+        let simplified = result
+            .get_region_polygons(region.id, 2.)
+            .expect("vectorization should not fail");
+        assert!(simplified.len() <= polygons.len());
+        for polygon in &simplified {
+            assert!(polygon.exterior.len() >= 3 && ring_area(&polygon.exterior) > 0);
+            for hole in &polygon.holes {
+                assert!(hole.len() >= 3 && ring_area(hole) < 0);
+            }
+        }
+    }
+
+    let geojson = result
+        .export_polygons(PolygonFormat::GeoJson, 1.)
+        .expect("export should not fail");
+    assert_eq!(geojson.matches("\"Feature\"").count(), result.regions.len());
+    let svg = result
+        .export_polygons(PolygonFormat::Svg, 1.)
+        .expect("export should not fail");
+    assert_eq!(svg.matches("<path ").count(), result.regions.len());
+}
+
+#[test]
+fn test_polygon_rings() {
+    use opencv::core::{Point, Point2d, Rect, Scalar};
+
+    // region 1 is a 3x3 square with a hole in the middle, and a pixel touching it only at a corner
+    #[rustfmt::skip]
+    let labels = [
+        1, 0, 0, 0, 0,
+        0, 1, 1, 1, 0,
+        0, 1, 0, 1, 0,
+        0, 1, 1, 1, 0,
+        0, 0, 0, 0, 0,
+    ];
+    let labels = Mat::new_rows_cols_with_data(5, 5, &labels)
+        .expect("mat creation should not fail")
+        .try_clone()
+        .expect("clone should not fail");
+    let region = |id, pixel_count, bounding_box| Region {
+        id,
+        pixel_count,
+        bounding_box,
+        centroid: Point2d::default(),
+        mean_color: Scalar::default(),
+    };
+    let result = SegmentationResult {
+        labels,
+        regions: vec![region(0, 16, Rect::new(0, 0, 5, 5)), region(1, 9, Rect::new(0, 0, 4, 4))],
+        leaf_to_region: std::collections::BTreeMap::new(),
+        edges: Vec::new(),
+        is_partial: false,
+        seed: DEFAULT_SEED,
+    };
+
+    let polygons = result.get_region_polygons(1, 0.).expect("vectorization should not fail");
+    let points = |points: &[(i32, i32)]| points.iter().map(|(x, y)| Point::new(*x, *y)).collect::<Vec<_>>();
+    assert_eq!(polygons, vec![
+        Polygon {
+            exterior: points(&[(0, 0), (1, 0), (1, 1), (0, 1)]),
+            holes: Vec::new(),
+        },
+        Polygon {
+            exterior: points(&[(1, 1), (4, 1), (4, 4), (1, 4)]),
+            holes: vec![points(&[(2, 2), (2, 3), (3, 3), (3, 2)])],
+        },
+    ]);
+}
+
+#[test]
+fn test_coco_export() {
+    let i = ImageContainer::new_from_file_color("./test_images/200x100_split.jpg")
+        .expect("test file must be present");
+
+    let splitter = splitter_traits::StdSplitter::new(10, 30.);
+    let merger = merger_traits::StdMerger::new(40.);
+    let logger = logger_traits::NullLogger::new();
+
+    let pool = WorkerPool::new(4).expect("worker pool creation should not fail");
+    let logic = MainLogic::new(splitter, merger, logger, &i, &pool);
+    let logic = logic.execute_split(4).expect("split phase should not fail");
+    let logic = logic.execute_merge(4).expect("merge phase should not fail");
+    let result = logic.get_result();
+
+    let counts = result.get_rle_counts().expect("rle encoding should not fail");
+    assert_eq!(counts.len(), result.regions.len());
+    for (region, counts) in result.regions.iter().zip(&counts) {
+        assert_eq!(counts.iter().sum::<usize>(), (i.width * i.height) as usize);
+        let inside = counts.iter().skip(1).step_by(2).sum::<usize>();
+        assert_eq!(inside, region.pixel_count);
+    }
+
+    let mut exporter = CocoExporter::new(CocoSegmentation::Rle);
+    assert_eq!(exporter.add_image("a.jpg", result).expect("export should not fail"), 1);
+    assert_eq!(exporter.add_image("b.jpg", result).expect("export should not fail"), 2);
+    let json = exporter.to_json();
+    assert_eq!(json.matches("\"file_name\"").count(), 2);
+    assert_eq!(json.matches("\"iscrowd\"").count(), 2 * result.regions.len());
+
+    let mut exporter = CocoExporter::new(CocoSegmentation::Polygon { tolerance: 1. });
+    exporter.add_image("a.jpg", result).expect("export should not fail");
+    assert_eq!(exporter.to_json().matches("\"bbox\"").count(), result.regions.len());
+
+    // with a huge tolerance every polygon collapses, and the regions fall back to rle
+    let mut exporter = CocoExporter::new(CocoSegmentation::Polygon { tolerance: 1e6 });
+    exporter.add_image("a.jpg", result).expect("export should not fail");
+    let json = exporter.to_json();
+    assert!(!json.contains("\"segmentation\":[]"));
+    assert_eq!(json.matches("\"counts\"").count(), result.regions.len());
+}
+
+#[test]
+fn test_benchmark_record() {
+    let i = ImageContainer::new_from_file_color("./test_images/200x100_split.jpg")
+        .expect("test file must be present");
+
+    let pool = WorkerPool::new(2).expect("worker pool creation should not fail");
+    let case = BenchmarkCase {
+        image_name: "200x100 \"split\", copy.jpg".into(),
+        splitter_name: "std".into(),
+        merger_name: "std".into(),
+        num_of_workers: 2,
+    };
+    let record = BenchmarkRecord::measure(
+        case,
+        &i,
+        splitter_traits::StdSplitter::new(10, 30.),
+        merger_traits::StdMerger::new(40.),
+        MergeStrategy::Greedy,
+        &pool,
+    )
+    .expect("benchmark should not fail");
+
+    assert!(record.statistics.splits > 0);
+    assert!(record.statistics.merge_rounds > 0);
+    assert!(record.num_of_regions > 0);
+
+    let csv = benchmark_report(&[record.clone(), record.clone()], BenchmarkFormat::Csv);
+    assert_eq!(csv.lines().count(), 3);
+    assert!(csv.starts_with(BenchmarkRecord::CSV_HEADER));
+    assert!(csv.lines().skip(1).all(|row| row.starts_with("\"200x100 \"\"split\"\", copy.jpg\",std,std,2,")));
+
+    let json = benchmark_report(&[record], BenchmarkFormat::Json);
+    assert!(json.starts_with("[{\"image\":\"200x100 \\\"split\\\", copy.jpg\","));
+}
+
+#[test]
+fn test_run_statistics() {
+    let i = ImageContainer::new_from_file_color("./test_images/200x100_split.jpg")
+        .expect("test file must be present");
+
+    let pool = WorkerPool::new(4).expect("worker pool creation should not fail");
+    for merge_strategy in [MergeStrategy::Greedy, MergeStrategy::BestFirst] {
+        let splitter = splitter_traits::StdSplitter::new(10, 30.);
+        let merger = merger_traits::StdMerger::new(40.);
+        let logger = logger_traits::NullLogger::new();
+
+        let logic = MainLogic::new(splitter, merger, logger, &i, &pool)
+            .with_merge_strategy(merge_strategy);
+        let logic = logic.execute_split(4).expect("split phase should not fail");
+        let logic = logic.execute_merge(4).expect("merge phase should not fail");
+        let (result, statistics) = logic.into_result_and_statistics();
+
+        // every split is a binary split, and every accepted merge removes one region
+        assert_eq!(statistics.peak_regions, statistics.splits + 1);
+        assert_eq!(statistics.peak_regions - statistics.accepted_merges, result.regions.len());
+        assert!(statistics.splitter_calls > statistics.splits);
+        assert!(statistics.merger_calls >= statistics.accepted_merges + statistics.rejected_merges);
+        assert!(statistics.split_rounds > 0);
+        assert!(statistics.pool_time <= statistics.split_time + statistics.merge_time);
+    }
+}
+
+#[test]
+fn test_progress_and_cancellation() {
+    let i = ImageContainer::new_from_file_color("./test_images/200x100_split.jpg")
+        .expect("test file must be present");
+
+    let pool = WorkerPool::new(4).expect("worker pool creation should not fail");
+    let splitter = splitter_traits::StdSplitter::new(10, 30.);
+    let merger = merger_traits::StdMerger::new(40.);
+    let logger = logger_traits::NullLogger::new();
+
+    // cancel the run at the first progress report, after the first round of splits
+    let cancellation_token = CancellationToken::new();
+    let token = cancellation_token.clone();
+    let mut reports = Vec::new();
+    let logic = MainLogic::new(splitter, merger, logger, &i, &pool)
+        .with_cancellation_token(cancellation_token)
+        .with_progress_callback(|progress| {
+            reports.push(progress);
+            token.cancel();
+        });
+    let logic = logic.execute_split(4).expect("split phase should not fail");
+    let logic = logic.execute_merge(4).expect("merge phase should not fail");
+    let (result, statistics) = logic.into_result_and_statistics();
+
+    // the merge phase is skipped, so it never reports its progress
+    assert_eq!(
+        reports,
+        vec![Progress::Split {
+            round: 1,
+            split_areas: 1,
+            pending: 2
+        }]
+    );
+    assert!(statistics.cancelled);
+    assert_eq!(statistics.split_rounds, 1);
+    assert_eq!(statistics.merge_rounds, 0);
+    assert_eq!(statistics.merger_calls, 0);
+
+    // the partial result is still a valid segmentation of the whole image
+    assert!(result.is_partial);
+    assert_eq!(result.regions.len(), 2);
+    let pixel_count: usize = result.regions.iter().map(|r| r.pixel_count).sum();
+    assert_eq!(pixel_count, (i.width * i.height) as usize);
+    assert_eq!(result.edges.len(), 1);
+}
+
+#[test]
+fn test_time_limit() {
+    let i = ImageContainer::new_from_file_color("./test_images/200x100_split.jpg")
+        .expect("test file must be present");
+
+    let pool = WorkerPool::new(4).expect("worker pool creation should not fail");
+    let splitter = splitter_traits::StdSplitter::new(10, 30.);
+    let merger = merger_traits::StdMerger::new(40.);
+    let logger = logger_traits::NullLogger::new();
+
+    let token = CancellationToken::new().with_time_limit(std::time::Duration::from_secs(3600));
+    assert!(!token.is_cancelled());
+
+    // the time limit is already over when the run starts, so the image is never split
+    let token = CancellationToken::new().with_time_limit(std::time::Duration::ZERO);
+    assert!(token.is_cancelled());
+    let logic = MainLogic::new(splitter, merger, logger, &i, &pool).with_cancellation_token(token);
+    let logic = logic.execute_split(4).expect("split phase should not fail");
+    let logic = logic.execute_merge(4).expect("merge phase should not fail");
+    let (result, statistics) = logic.into_result_and_statistics();
+    assert!(statistics.cancelled && result.is_partial);
+    assert_eq!(statistics.splits, 0);
+    assert_eq!(result.regions.len(), 1);
+}
+
+#[test]
+fn test_deterministic_runs() {
+    let i = ImageContainer::new_from_file_color("./test_images/200x100_split.jpg")
+        .expect("test file must be present");
+
+    let pool = WorkerPool::new(4).expect("worker pool creation should not fail");
+    for merge_strategy in [MergeStrategy::Greedy, MergeStrategy::BestFirst] {
+        let mut outputs = Vec::new();
+        for num_of_workers in [1, 1, 2, 4] {
+            let splitter = splitter_traits::HeuristicAsymmetricSplitter::new(
+                splitter_traits::StdSplitter::new(3, 10.),
+            );
+            let merger = merger_traits::StdMerger::new(40.);
+            let logger = logger_traits::ImageLogger::new(i.image.clone())
+                .expect("logger creation should not fail");
+
+            let logic = MainLogic::new(splitter, merger, logger, &i, &pool)
+                .with_merge_strategy(merge_strategy)
+                .with_seed(42);
+            let logic = logic.execute_split(num_of_workers).expect("split phase should not fail");
+            let logic = logic.execute_merge(num_of_workers).expect("merge phase should not fail");
+            let result = logic.into_result();
+
+            let labels = result
+                .labels
+                .data_typed::<i32>()
+                .expect("labels should be CV_32S")
+                .to_vec();
+            let overlay = result
+                .get_overlay(&i.image, OverlayKind::FalseColor)
+                .expect("overlay should not fail")
+                .data_bytes()
+                .expect("overlay should be continuous")
+                .to_vec();
+            outputs.push((labels, result.export_graph(GraphFormat::Json), overlay));
+        }
+        assert!(outputs.windows(2).all(|w| w[0] == w[1]));
+    }
 }
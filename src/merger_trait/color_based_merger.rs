@@ -1,9 +1,24 @@
 use opencv::core::Scalar;
+
 use super::*;
 
+/// how the distance between two colors is measured by the `ColorBasedMerger`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ColorDistance {
+    /// euclidean distance between the channels, in whatever color space the image is
+    #[default]
+    Euclidean,
+    /// CIE76 ΔE distance, the image must be in the `ColorSpace::Lab` color space
+    DeltaE,
+    /// euclidean distance in the `ColorSpace::Hsv` color space, the difference between two hues
+    /// is taken the short way around the hue circle
+    Hsv,
+}
+
 pub struct ColorBasedMerger {
     color_distance_threshold: f64,
     std_distance_threshold: f64,
+    color_distance: ColorDistance,
 }
 
 impl ColorBasedMerger {
@@ -11,26 +26,36 @@ impl ColorBasedMerger {
         return Self {
             color_distance_threshold,
             std_distance_threshold,
+            color_distance: ColorDistance::default(),
         };
     }
-}
 
-fn calculate_masked_average(matrix: &Mat, mask: &Mat) -> Result<(Scalar, Scalar)> {
-    // Verify input dimensions match
-    
-    if matrix.size()? != mask.size()? {
-        return Err(anyhow!("size missmatch"));
+    pub fn with_color_distance(mut self, color_distance: ColorDistance) -> Self {
+        self.color_distance = color_distance;
+        return self;
     }
 
-    // Calculate mean only for pixels where mask is non-zero
-    let mut mean = Scalar::default();
-    let mut std_dev = Scalar::default();
-
-    for _ in 0..1000{
-        opencv::core::mean_std_dev(&matrix, &mut mean, &mut std_dev, &mask)?;
+    /// distance between the mean colors of two areas
+    fn color_distance(&self, delta: Scalar) -> f64 {
+        return match self.color_distance {
+            ColorDistance::Hsv => {
+                let hue = delta[0].abs() % HUE_RANGE;
+                eucledian_distance(&[hue.min(HUE_RANGE - hue), delta[1], delta[2]])
+            }
+            _ => self.std_distance(delta),
+        };
     }
 
-    Ok((mean, std_dev))
+    /// distance between the standard deviations of two areas, the standard deviation is not
+    /// circular even for the hue
+    fn std_distance(&self, delta: Scalar) -> f64 {
+        return match self.color_distance {
+            ColorDistance::Euclidean | ColorDistance::Hsv => eucledian_distance(delta.as_slice()),
+            // the 8 bit encoding of OpenCV scales L from 0..100 to 0..255 (the offset of a and b
+            // cancels out in the difference), the standard deviation of L is scaled the same way
+            ColorDistance::DeltaE => eucledian_distance(&[delta[0] * 100. / 255., delta[1], delta[2]]),
+        };
+    }
 }
 
 fn eucledian_distance(items: &[f64]) -> f64{
@@ -42,16 +67,17 @@ fn eucledian_distance(items: &[f64]) -> f64{
 }
 
 impl MergerTrait for ColorBasedMerger {
-    fn merge(&self, mask_a: &Mat, mask_b: &Mat, image: &Mat) -> bool {
-        let (color_a,std_a) = calculate_masked_average(image, mask_a)
-            .expect("matrix calculation went wrong");
-        let (color_b,std_b) = calculate_masked_average(image, mask_b)
-            .expect("matrix calculation went wrong");
-        let delta_color = color_a - color_b;
-        let delta_std = std_a - std_b;
-        let delta_color = eucledian_distance(delta_color.as_slice());
-        let delta_std = eucledian_distance(delta_std.as_slice());
-        return delta_color < self.color_distance_threshold &&
-               delta_std < self.std_distance_threshold;
+    /// the color and std distances, each normalized by its own threshold (the worst of the two
+    /// is returned), so that the areas are merged only when both are below their threshold
+    fn score(&self, stats_a: &RegionStatistics, stats_b: &RegionStatistics) -> Result<f64> {
+        let delta_color = self.color_distance(stats_a.mean() - stats_b.mean());
+        let delta_std = self.std_distance(stats_a.std_dev() - stats_b.std_dev());
+        return Ok(f64::max(
+            delta_color / self.color_distance_threshold,
+            delta_std / self.std_distance_threshold,
+        ));
+    }
+    fn threshold(&self) -> f64 {
+        return 1.;
     }
 }
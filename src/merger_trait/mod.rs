@@ -1,13 +1,49 @@
+#[cfg(test)]
+mod test;
 mod blind_merger;
 pub use blind_merger::BlindMerger;
 mod color_based_merger;
-pub use color_based_merger::ColorBasedMerger;
+pub use color_based_merger::{ColorBasedMerger, ColorDistance};
 mod std_merger;
 pub use std_merger::StdMerger;
+mod texture_merger;
+pub use texture_merger::TextureMerger;
 
 
 use crate::prelude::*;
-/// trait that can represent different merging strategies
+/// trait that can represent different merging strategies.
+/// The decision is taken only from the statistics of the two areas, that are kept up to date
+/// during the merge phase, so no pixel needs to be scanned
 pub trait MergerTrait: Sync + 'static{
-    fn merge(&self, mask_a: &Mat, mask_b: &Mat, image: &Mat) -> bool;
+    /// how different the two areas are (lower is more similar). It is also used by
+    /// `MergeStrategy::BestFirst` to decide which couple is evaluated first
+    fn score(&self, stats_a: &RegionStatistics, stats_b: &RegionStatistics) -> Result<f64>;
+
+    /// two areas are merged only if their score is lower than this threshold
+    fn threshold(&self) -> f64;
+
+    fn merge(&self, stats_a: &RegionStatistics, stats_b: &RegionStatistics) -> Result<bool> {
+        return Ok(self.score(stats_a, stats_b)? < self.threshold());
+    }
+
+    /// whether the statistics must also contain the texture histogram of the areas
+    fn requires_texture(&self) -> bool {
+        return false;
+    }
 }
+
+impl MergerTrait for Box<dyn MergerTrait> {
+    fn score(&self, stats_a: &RegionStatistics, stats_b: &RegionStatistics) -> Result<f64> {
+        return (**self).score(stats_a, stats_b);
+    }
+    fn threshold(&self) -> f64 {
+        return (**self).threshold();
+    }
+    fn merge(&self, stats_a: &RegionStatistics, stats_b: &RegionStatistics) -> Result<bool> {
+        return (**self).merge(stats_a, stats_b);
+    }
+    fn requires_texture(&self) -> bool {
+        return (**self).requires_texture();
+    }
+}
+
@@ -1,6 +1,3 @@
-
-use opencv::core::{bitwise_or, mean_std_dev, no_array, Scalar};
-
 use super::*;
 
 pub struct StdMerger {
@@ -16,30 +13,18 @@ impl StdMerger {
 }
 
 impl MergerTrait for StdMerger {
-    fn merge(&self, mask_a: &Mat, mask_b: &Mat, image: &Mat) -> bool {
-        
-        let mut mask = Mat::default();
-        bitwise_or(mask_a, mask_b, &mut mask, &no_array())
-            .expect("error in std merger");
-
-        let mut mean = Scalar::default();
-        let mut std = Scalar::default();
-        mean_std_dev(
-            &image,
-            &mut mean,
-            &mut std,
-            &mask,
-        ).expect("eror in huestd splitter");
-
-
-        // dbg!(std);
+    /// standard deviation of the area that would be obtained by merging the two
+    fn score(&self, stats_a: &RegionStatistics, stats_b: &RegionStatistics) -> Result<f64> {
+        let std = RegionStatistics::merge(stats_a, stats_b).std_dev();
 
         let distance = std.as_slice()
             .iter().map(|x| x.powi(2))
             .sum::<f64>()
             .sqrt();
 
-        return distance < self.std_threshold;
+        return Ok(distance);
+    }
+    fn threshold(&self) -> f64 {
+        return self.std_threshold;
     }
-    
 }
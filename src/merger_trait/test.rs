@@ -0,0 +1,84 @@
+use crate::prelude::*;
+
+fn uniform_statistics(pixel_count: usize, color: [f64; 3]) -> RegionStatistics {
+    let n = pixel_count as f64;
+    return RegionStatistics {
+        pixel_count,
+        sum: [color[0] * n, color[1] * n, color[2] * n, 0.],
+        sum_sq: [color[0].powi(2) * n, color[1].powi(2) * n, color[2].powi(2) * n, 0.],
+        texture: None,
+    };
+}
+
+#[test]
+fn test_score_is_consistent_with_merge() {
+    let a = uniform_statistics(100, [10., 10., 10.]);
+    let b = uniform_statistics(50, [10., 10., 40.]);
+    let c = uniform_statistics(50, [10., 10., 200.]);
+
+    let mergers: Vec<Box<dyn MergerTrait>> = vec![
+        Box::new(merger_traits::ColorBasedMerger::new(70., 250.)),
+        Box::new(merger_traits::StdMerger::new(40.)),
+        Box::new(merger_traits::BlindMerger::new()),
+    ];
+
+    for merger in mergers {
+        let score_ab = merger.score(&a, &b).expect("score should not fail");
+        let score_ac = merger.score(&a, &c).expect("score should not fail");
+        assert!(score_ab <= score_ac);
+
+        for (x, y, score) in [(&a, &b, score_ab), (&a, &c, score_ac)] {
+            let to_merge = merger.merge(x, y).expect("merge should not fail");
+            assert_eq!(to_merge, score < merger.threshold());
+        }
+    }
+
+    let merger = merger_traits::ColorBasedMerger::new(70., 250.);
+    assert!(merger.merge(&a, &b).expect("merge should not fail"));
+    assert!(!merger.merge(&a, &c).expect("merge should not fail"));
+}
+
+#[test]
+fn test_delta_e_scales_lightness() {
+    // a difference of 255 in the 8 bit L channel is a difference of 100 in CIELAB
+    let a = uniform_statistics(100, [0., 128., 128.]);
+    let b = uniform_statistics(100, [255., 128., 128.]);
+
+    let merger = merger_traits::ColorBasedMerger::new(1., 1.)
+        .with_color_distance(merger_traits::ColorDistance::DeltaE);
+    let score = merger.score(&a, &b).expect("score should not fail");
+    assert!((score - 100.).abs() < 1e-6);
+
+    let merger = merger_traits::ColorBasedMerger::new(1., 1.);
+    let score = merger.score(&a, &b).expect("score should not fail");
+    assert!((score - 255.).abs() < 1e-6);
+
+    // the standard deviation of L is scaled in the same way: the same mean, but half of the
+    // pixels at L=0 and half at L=255 (a standard deviation of 127.5, that is 50 in CIELAB)
+    let a = RegionStatistics::merge(
+        &uniform_statistics(50, [0., 128., 128.]),
+        &uniform_statistics(50, [255., 128., 128.]),
+    );
+    let b = uniform_statistics(100, [127.5, 128., 128.]);
+
+    let merger = merger_traits::ColorBasedMerger::new(1., 1.)
+        .with_color_distance(merger_traits::ColorDistance::DeltaE);
+    let score = merger.score(&a, &b).expect("score should not fail");
+    assert!((score - 50.).abs() < 1e-6);
+}
+
+#[test]
+fn test_hsv_distance_wraps_the_hue() {
+    // the two reds are at the opposite ends of the hue range
+    let a = uniform_statistics(100, [2., 200., 200.]);
+    let b = uniform_statistics(100, [HUE_RANGE - 2., 200., 200.]);
+
+    let merger = merger_traits::ColorBasedMerger::new(1., 1.)
+        .with_color_distance(merger_traits::ColorDistance::Hsv);
+    let score = merger.score(&a, &b).expect("score should not fail");
+    assert!((score - 4.).abs() < 1e-6);
+
+    let merger = merger_traits::ColorBasedMerger::new(1., 1.);
+    let score = merger.score(&a, &b).expect("score should not fail");
+    assert!((score - (HUE_RANGE - 4.)).abs() < 1e-6);
+}
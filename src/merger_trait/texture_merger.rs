@@ -0,0 +1,47 @@
+use super::*;
+
+/// merger that compares both the texture (local binary pattern histograms) and the mean color
+/// of the areas, so that uniformly textured areas are merged even if their variance is high
+pub struct TextureMerger {
+    texture_distance_threshold: f64,
+    color_distance_threshold: f64,
+}
+
+impl TextureMerger {
+    pub fn new(texture_distance_threshold: f64, color_distance_threshold: f64) -> Self {
+        return Self {
+            texture_distance_threshold,
+            color_distance_threshold,
+        };
+    }
+}
+
+impl MergerTrait for TextureMerger {
+    /// the texture and color distances, each normalized by its own threshold (the worst of the
+    /// two is returned)
+    fn score(&self, stats_a: &RegionStatistics, stats_b: &RegionStatistics) -> Result<f64> {
+        let (texture_a, texture_b) = match (&stats_a.texture, &stats_b.texture) {
+            (Some(a), Some(b)) => (a, b),
+            _ => return Err(anyhow!("the statistics don't contain the texture histograms")),
+        };
+        let delta_texture = texture_a.chi_square_distance(texture_b);
+
+        let delta_color = (stats_a.mean() - stats_b.mean())
+            .as_slice()
+            .iter()
+            .map(|x| x.powi(2))
+            .sum::<f64>()
+            .sqrt();
+
+        return Ok(f64::max(
+            delta_texture / self.texture_distance_threshold,
+            delta_color / self.color_distance_threshold,
+        ));
+    }
+    fn threshold(&self) -> f64 {
+        return 1.;
+    }
+    fn requires_texture(&self) -> bool {
+        return true;
+    }
+}
@@ -17,7 +17,9 @@ pub mod merger_traits {
     pub use crate::merger_trait::{
         BlindMerger,
         ColorBasedMerger,
-        StdMerger
+        ColorDistance,
+        StdMerger,
+        TextureMerger
     };
 }
 
@@ -28,12 +30,21 @@ pub mod splitter_traits {
         HueStdSplitter,
         StdSplitter,
         MaxDeltaSplitter,
-        HeuristicAsymmetricSplitter
+        TextureSplitter,
+        HeuristicAsymmetricSplitter,
+        VarianceSplitter,
+        QuadSplitter
     };
 }
 
 pub use crate::main_logic::*;
 
+pub use crate::evaluation::*;
+
+pub use crate::benchmark::*;
+
+pub use crate::worker_pool::{WorkerPool, JobHandle};
+
 pub use opencv::prelude::*;
 pub use anyhow::{anyhow,Result};
 
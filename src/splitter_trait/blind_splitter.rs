@@ -5,27 +5,31 @@ pub struct BlindSplitter{
     min_split_size: i32
 }
 impl BlindSplitter {
+    /// an area must be at least 2 pixels wide to be split, smaller values of `min_split_size`
+    /// are raised to 2
     pub fn new(min_split_size: i32) -> Self{
-        assert!(min_split_size >= 2,"an image must be at least a 2x2 picture for split to take effect");
-        Self{min_split_size}
+        Self{min_split_size: min_split_size.max(2)}
     }
 }
 
 impl SplitterTrait for BlindSplitter {
-    fn split(&self, image: &Mat) -> Option<(crate::image_container::CutDirection, i32)> {
-        let size = image.size().expect("valid image must be passed");
-        if size.height > size.width{
+    fn split(&self, image: &ImageContainerSplit<'_>) -> Result<Option<Split>> {
+        if image.height > image.width{
             // split the height
-            if size.height < self.min_split_size {
-                return None;
+            if image.height < self.min_split_size {
+                return Ok(None);
             }
-            return Some((CutDirection::CutParallelToX, size.height/2));
+            return Ok(Some(Split::Binary(CutDirection::CutParallelToX, image.height/2)));
         }else{
             // split the width
-            if size.width < self.min_split_size {
-                return None;
+            if image.width < self.min_split_size {
+                return Ok(None);
             }
-            return Some((CutDirection::CutParallelToY, size.width/2));
+            return Ok(Some(Split::Binary(CutDirection::CutParallelToY, image.width/2)));
         }
     } 
+
+    fn min_split_size(&self) -> i32 {
+        return self.min_split_size;
+    }
 }
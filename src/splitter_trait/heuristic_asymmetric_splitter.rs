@@ -15,22 +15,22 @@ impl<T: SplitterTrait> HeuristicAsymmetricSplitter<T> {
         Self { decision_splitter }
     }
 
-    fn is_split_too_asymetric(&self, cut_direction: CutDirection, cut_at: i32, image: &Mat) -> bool {
+    fn is_split_too_asymetric(&self, cut_direction: CutDirection, cut_at: i32, image: &ImageContainerSplit<'_>) -> bool {
 
-        let mut height_after_split = image.rows();
-        let mut width_after_split = image.cols();
+        let mut height_after_split = image.height;
+        let mut width_after_split = image.width;
 
         if cut_at == 0 {
             return true
         }
 
         if cut_direction == CutDirection::CutParallelToX {
-            if cut_at == image.rows() {
+            if cut_at == image.height {
                 return true
             }
             height_after_split = cut_at.min(height_after_split-cut_at)
         } else {
-            if cut_at == image.cols() {
+            if cut_at == image.width {
                 return true
             }
             width_after_split = cut_at.min(width_after_split-cut_at)
@@ -42,33 +42,33 @@ impl<T: SplitterTrait> HeuristicAsymmetricSplitter<T> {
 }
 
 impl<T: SplitterTrait> SplitterTrait for HeuristicAsymmetricSplitter<T> {
-    fn split(&self, image: &Mat) -> Option<(CutDirection, i32)> {
-        if image.rows() <= 5 || image.cols() <= 5
+    fn split(&self, image: &ImageContainerSplit<'_>) -> Result<Option<Split>> {
+        if image.height <= 5 || image.width <= 5
         {
             return self.decision_splitter.split(image);
         }
 
-        let (original_direction, original_cut) = self.decision_splitter.split(image)?;
+        // the cut chosen by the decision splitter is used as fallback
+        let original_split = match self.decision_splitter.split(image)? {
+            Some(e) => e,
+            None => return Ok(None),
+        };
 
-        let kernel_x = Mat::from_slice_2d(&[[-1], [1]]).expect("error in kernel creation");
-        let kernel_x_flip = Mat::from_slice_2d(&[[1], [-1]]).expect("error in kernel creation");
-        let kernel_y = Mat::from_slice_2d(&[[-1, 1]]).expect("error in kernel creation");
-        let kernel_y_flip = Mat::from_slice_2d(&[[1, -1]]).expect("error in kernel creation");
+        let kernel_x = Mat::from_slice_2d(&[[-1], [1]])?;
+        let kernel_x_flip = Mat::from_slice_2d(&[[1], [-1]])?;
+        let kernel_y = Mat::from_slice_2d(&[[-1, 1]])?;
+        let kernel_y_flip = Mat::from_slice_2d(&[[1, -1]])?;
 
         let mut derivate_mat_x_straight = Mat::default();
         let mut derivate_mat_y_straight = Mat::default();
         let mut derivate_mat_x_flip = Mat::default();
         let mut derivate_mat_y_flip = Mat::default();
 
-        filter_2d( image, &mut derivate_mat_x_straight, 0, &kernel_x, Point::new(0, 0), 0., BORDER_DEFAULT,)
-        .expect("error in partial derivate");
-        filter_2d( image, &mut derivate_mat_x_flip, 0, &kernel_x_flip, Point::new(0, 0), 0., BORDER_DEFAULT,)
-        .expect("error in partial derivate");
+        filter_2d( &image.image, &mut derivate_mat_x_straight, 0, &kernel_x, Point::new(0, 0), 0., BORDER_DEFAULT,)?;
+        filter_2d( &image.image, &mut derivate_mat_x_flip, 0, &kernel_x_flip, Point::new(0, 0), 0., BORDER_DEFAULT,)?;
 
-        filter_2d( image, &mut derivate_mat_y_straight, 0, &kernel_y, Point::new(0, 0), 0., BORDER_DEFAULT,)
-        .expect("error in partial derivate");
-        filter_2d( image, &mut derivate_mat_y_flip, 0, &kernel_y_flip, Point::new(0, 0), 0., BORDER_DEFAULT,)
-        .expect("error in partial derivate");
+        filter_2d( &image.image, &mut derivate_mat_y_straight, 0, &kernel_y, Point::new(0, 0), 0., BORDER_DEFAULT,)?;
+        filter_2d( &image.image, &mut derivate_mat_y_flip, 0, &kernel_y_flip, Point::new(0, 0), 0., BORDER_DEFAULT,)?;
 
         let mut derifate_mat_x = Mat::default();
         let mut derifate_mat_y = Mat::default();
@@ -79,27 +79,28 @@ impl<T: SplitterTrait> SplitterTrait for HeuristicAsymmetricSplitter<T> {
             &mut derifate_mat_x,
             &no_array(),
             -1,
-        )
-        .expect("error in partial derivate add");
+        )?;
         add(
             &derivate_mat_y_flip,
             &derivate_mat_y_straight,
             &mut derifate_mat_y,
             &no_array(),
             -1,
-        )
-        .expect("error in partial derivate add");
-
-        let mut derivate_mat_gray_x = Mat::default();
-        let mut derivate_mat_gray_y = Mat::default();
+        )?;
 
-        cvt_color(&derifate_mat_x, &mut derivate_mat_gray_x, COLOR_BGR2GRAY, 0)
-            .expect("error in color converstion function");
-        cvt_color(&derifate_mat_y, &mut derivate_mat_gray_y, COLOR_BGR2GRAY, 0)
-            .expect("error in color converstion function");
+        // grayscale images (see `ColorSpace::Gray`) don't need to be converted
+        let (derivate_mat_gray_x, derivate_mat_gray_y) = if derifate_mat_x.channels() == 1 {
+            (derifate_mat_x, derifate_mat_y)
+        } else {
+            let mut derivate_mat_gray_x = Mat::default();
+            let mut derivate_mat_gray_y = Mat::default();
+            cvt_color(&derifate_mat_x, &mut derivate_mat_gray_x, COLOR_BGR2GRAY, 0)?;
+            cvt_color(&derifate_mat_y, &mut derivate_mat_gray_y, COLOR_BGR2GRAY, 0)?;
+            (derivate_mat_gray_x, derivate_mat_gray_y)
+        };
 
-        let len_y = image.cols();
-        let len_x = image.rows();
+        let len_y = image.width;
+        let len_x = image.height;
 
         let mut output_x = Mat::default();
         let mut output_y = Mat::default();
@@ -110,8 +111,7 @@ impl<T: SplitterTrait> SplitterTrait for HeuristicAsymmetricSplitter<T> {
             1,
             ReduceTypes::REDUCE_SUM.into(),
             CV_32F,
-        )
-        .expect("error while calculating the max value");
+        )?;
 
         reduce(
             &derivate_mat_gray_y,
@@ -119,15 +119,14 @@ impl<T: SplitterTrait> SplitterTrait for HeuristicAsymmetricSplitter<T> {
             0,
             ReduceTypes::REDUCE_SUM.into(),
             CV_32F,
-        )
-        .expect("error while calculating the max value");
+        )?;
 
         let output_x = (0..len_x)
-            .map(|i| *output_x.at::<f32>(i).expect("error in output creation"))
-            .collect::<Vec<_>>();
+            .map(|i| output_x.at::<f32>(i).copied())
+            .collect::<opencv::Result<Vec<_>>>()?;
         let output_y: Vec<f32> = (0..len_y)
-            .map(|i| *output_y.at::<f32>(i).expect("error in output creation"))
-            .collect::<Vec<_>>();
+            .map(|i| output_y.at::<f32>(i).copied())
+            .collect::<opencv::Result<Vec<_>>>()?;
 
 
 
@@ -135,7 +134,7 @@ impl<T: SplitterTrait> SplitterTrait for HeuristicAsymmetricSplitter<T> {
             .into_iter()
             .enumerate()
             .max_by(|a, b| a.1.total_cmp(&b.1))
-            .expect("matrix must have at least a size");
+            .ok_or(anyhow!("matrix must have at least a size"))?;
         max_x *= len_x as f32;
         max_index_x += 1;
 
@@ -143,12 +142,12 @@ impl<T: SplitterTrait> SplitterTrait for HeuristicAsymmetricSplitter<T> {
             .into_iter()
             .enumerate()
             .max_by(|a, b| a.1.total_cmp(&b.1))
-            .expect("matrix must have at least a size");
+            .ok_or(anyhow!("matrix must have at least a size"))?;
         max_y *= len_y as f32;
         max_index_y += 1;
 
         if max_x == 0. && max_y == 0. {
-            return Some((original_direction, original_cut));
+            return Ok(Some(original_split));
         }
 
         let (cut_direction, cut_at) = if max_x > max_y {
@@ -158,8 +157,12 @@ impl<T: SplitterTrait> SplitterTrait for HeuristicAsymmetricSplitter<T> {
         };
 
         if self.is_split_too_asymetric(cut_direction, cut_at, image) {
-            return Some((original_direction, original_cut));
+            return Ok(Some(original_split));
         }
-        return Some((cut_direction, cut_at));
+        return Ok(Some(Split::Binary(cut_direction, cut_at)));
+    }
+
+    fn min_split_size(&self) -> i32 {
+        return self.decision_splitter.min_split_size();
     }
 }
@@ -1,8 +1,3 @@
-use opencv::{
-    core::{mean_std_dev, no_array, split, Scalar},
-    imgproc::{cvt_color, COLOR_BGR2HSV},
-};
-
 use super::*;
 
 pub struct HueStdSplitter {
@@ -20,26 +15,17 @@ impl HueStdSplitter {
 }
 
 impl SplitterTrait for HueStdSplitter {
-    fn split(&self, image: &Mat) -> Option<(CutDirection, i32)> {
-        let mut hsv = Mat::default();
-        cvt_color(image, &mut hsv, COLOR_BGR2HSV, 0).expect("error in splitter trait");
-
-        let mut hsv_split = opencv::core::Vector::<Mat>::new();
-        split(&hsv, &mut hsv_split).expect("error in hue std splitter");
-
-        let mut mean = Scalar::default();
-        let mut std = Scalar::default();
-        mean_std_dev(
-            &hsv_split.get(0).expect("error in huestd splitter"),
-            &mut mean,
-            &mut std,
-            &no_array(),
-        ).expect("eror in huestd splitter");
+    fn split(&self, image: &ImageContainerSplit<'_>) -> Result<Option<Split>> {
+        let std = image.get_hue_statistics()?.std_dev();
 
         if std.as_slice()[0] > self.std_threshold {
             return self.blind_splitter.split(image)
         } else {
-            return None
+            return Ok(None)
         }
     }
+
+    fn min_split_size(&self) -> i32 {
+        return self.blind_splitter.min_split_size();
+    }
 }
@@ -1,5 +1,6 @@
 use opencv::{
-    core::{absdiff, mean_std_dev, min_max_loc, no_array, pow, reduce, Scalar, Size, BORDER_DEFAULT}, highgui::{imshow, wait_key}, imgproc::gaussian_blur
+    core::{absdiff, mean, min_max_loc, no_array, pow, reduce, Size, BORDER_DEFAULT, BORDER_ISOLATED},
+    imgproc::gaussian_blur,
 };
 
 use super::*;
@@ -19,47 +20,35 @@ impl MaxDeltaSplitter {
 }
 
 impl SplitterTrait for MaxDeltaSplitter {
-    fn split(&self, image: &Mat) -> Option<(CutDirection, i32)> {
+    fn split(&self, image: &ImageContainerSplit<'_>) -> Result<Option<Split>> {
         
+        // the split is a view on the whole image, `BORDER_ISOLATED` prevents the kernel of the
+        // blur from picking up the pixels that are outside of the split
         let mut blur = Mat::default();
-
-        // note: the image cloning here is because, if the image is a slice the
-        // function will pick up items from the bordering when applying the kernel for the blur
-        gaussian_blur(&image.clone(), &mut blur,Size::new(9, 9), 3., 0., BORDER_DEFAULT)
-            .expect("error in gaussian_blur");
-
-        let mut mean = Scalar::default();
-        let mut std = Scalar::default();
-        mean_std_dev(
-            &blur,
-            &mut mean,
-            &mut std,
-            &no_array(),
-        ).expect("eror in huestd splitter");
+        gaussian_blur(
+            &image.image,
+            &mut blur,
+            Size::new(9, 9),
+            3.,
+            0.,
+            BORDER_DEFAULT | BORDER_ISOLATED,
+        )?;
+        let mean = mean(&blur, &no_array())?;
 
         let average_color_mat = Mat::new_rows_cols_with_default(
-            image.rows(),
-            image.cols(),
-            image.typ(),
+            image.height,
+            image.width,
+            image.image.typ(),
             mean
-        ).expect("error im mat creation");
+        )?;
 
         let mut abs_diff_mat = Mat::default();
-        absdiff(&blur, &average_color_mat, &mut abs_diff_mat).expect("error in mat distance");
-
-        
-        // imshow("absdif", &abs_diff_mat).unwrap();
-        // wait_key(0).unwrap();
-
+        absdiff(&blur, &average_color_mat, &mut abs_diff_mat)?;
 
         // Square the differences for each channel
         let mut squared_diff = Mat::default();
-        pow(&abs_diff_mat, 2., &mut squared_diff)
-            .expect("error in power of two");
+        pow(&abs_diff_mat, 2., &mut squared_diff)?;
 
-
-        // imshow("pow", &squared_diff).unwrap();
-        
         let mut distance_mat = Mat::default();
         reduce(
             &squared_diff,
@@ -67,23 +56,23 @@ impl SplitterTrait for MaxDeltaSplitter {
             2, // Reduce along the color channels (axis 2)
             opencv::core::REDUCE_SUM,
             opencv::core::CV_64F,
-        ).expect("error in reduce");
+        )?;
 
         // Find the maximum value in the resulting matrix
         let mut min_val = 0.0;
         let mut max_val = 0.0;
-        min_max_loc(&distance_mat, Some(&mut min_val), Some(&mut max_val), None, None, &no_array())
-        .expect("error in min max loc");
+        min_max_loc(&distance_mat, Some(&mut min_val), Some(&mut max_val), None, None, &no_array())?;
         
         let max_distance = max_val.sqrt();
-        // dbg!(max_distance);
-        // dbg!(self.delta_threshold);
-        // dbg!(max_distance > self.delta_threshold);
 
         if max_distance > self.delta_threshold{
             return self.blind_splitter.split(image)
         } else {
-            return None
+            return Ok(None)
         }
     }
+
+    fn min_split_size(&self) -> i32 {
+        return self.blind_splitter.min_split_size();
+    }
 }
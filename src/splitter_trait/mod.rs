@@ -1,3 +1,5 @@
+#[cfg(test)]
+mod test;
 use crate::prelude::*;
 
 mod blind_splitter;
@@ -12,11 +14,38 @@ pub use std_splitter::StdSplitter;
 mod max_delta_splitter;
 pub use max_delta_splitter::MaxDeltaSplitter;
 
+mod texture_splitter;
+pub use texture_splitter::TextureSplitter;
+
 pub mod heuristic_asymmetric_splitter;
 pub use heuristic_asymmetric_splitter::HeuristicAsymmetricSplitter;
 
+mod variance_splitter;
+pub use variance_splitter::VarianceSplitter;
+
+mod quad_splitter;
+pub use quad_splitter::QuadSplitter;
 
-/// trait that can represent different splitting strategies
+
+/// trait that can represent different splitting strategies, the split receives a view on the
+/// area of the image that is been evaluated, and returns how it must be split (`None` if the
+/// area must not be split)
 pub trait SplitterTrait: Sync + 'static{
-    fn split(&self, image: &Mat) -> Option<(CutDirection, i32)>;
+    fn split(&self, image: &ImageContainerSplit<'_>) -> Result<Option<Split>>;
+
+    /// smallest size of the areas the splitter accepts to split, the splitters that choose the
+    /// position of the cut themselves (like `VarianceSplitter`) don't produce areas smaller than it
+    fn min_split_size(&self) -> i32 {
+        return 1;
+    }
+}
+
+impl SplitterTrait for Box<dyn SplitterTrait> {
+    fn split(&self, image: &ImageContainerSplit<'_>) -> Result<Option<Split>> {
+        return (**self).split(image);
+    }
+
+    fn min_split_size(&self) -> i32 {
+        return (**self).min_split_size();
+    }
 }
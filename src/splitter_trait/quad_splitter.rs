@@ -0,0 +1,36 @@
+use super::*;
+
+/// splitter that splits the areas in four quadrants, like the classic split and merge algorithm.
+/// The decision of whether to split or not is delegated to the `decision_splitter`, areas that are
+/// too thin to be split in four fall back to the split of the decision splitter
+pub struct QuadSplitter<T: SplitterTrait> {
+    decision_splitter: T,
+}
+
+impl<T: SplitterTrait> QuadSplitter<T> {
+    pub fn new(decision_splitter: T) -> Self {
+        Self { decision_splitter }
+    }
+}
+
+impl<T: SplitterTrait> SplitterTrait for QuadSplitter<T> {
+    fn split(&self, image: &ImageContainerSplit<'_>) -> Result<Option<Split>> {
+        let original_split = match self.decision_splitter.split(image)? {
+            Some(e) => e,
+            None => return Ok(None),
+        };
+
+        if image.height < 2 || image.width < 2 {
+            return Ok(Some(original_split));
+        }
+
+        return Ok(Some(Split::Quad {
+            x: image.width / 2,
+            y: image.height / 2,
+        }));
+    }
+
+    fn min_split_size(&self) -> i32 {
+        return self.decision_splitter.min_split_size();
+    }
+}
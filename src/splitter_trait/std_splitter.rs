@@ -1,6 +1,3 @@
-
-use opencv::core::{mean_std_dev, no_array, Scalar};
-
 use super::*;
 
 pub struct StdSplitter {
@@ -18,19 +15,9 @@ impl StdSplitter {
 }
 
 impl SplitterTrait for StdSplitter {
-    fn split(&self, image: &Mat) -> Option<(CutDirection, i32)> {
-
-        let mut mean = Scalar::default();
-        let mut std = Scalar::default();
-        mean_std_dev(
-            &image,
-            &mut mean,
-            &mut std,
-            &no_array(),
-        ).expect("eror in huestd splitter");
+    fn split(&self, image: &ImageContainerSplit<'_>) -> Result<Option<Split>> {
 
-
-        // dbg!(std);
+        let std = image.get_statistics()?.std_dev();
 
         let distance = std.as_slice()
             .iter().map(|x| x.powi(2))
@@ -40,7 +27,11 @@ impl SplitterTrait for StdSplitter {
         if distance > self.std_threshold{
             return self.blind_splitter.split(image)
         } else {
-            return None
+            return Ok(None)
         }
     }
+
+    fn min_split_size(&self) -> i32 {
+        return self.blind_splitter.min_split_size();
+    }
 }
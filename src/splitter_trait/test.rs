@@ -0,0 +1,65 @@
+use crate::prelude::*;
+use opencv::core::{Rect, Scalar, CV_8UC3};
+
+#[test]
+fn test_variance_splitter_cuts_at_the_edge() {
+    // black image with a white band on the right side, starting at column 7
+    let mut image = Mat::new_rows_cols_with_default(10, 20, CV_8UC3, Scalar::all(0.))
+        .expect("mat creation should not fail");
+    opencv::imgproc::rectangle(
+        &mut image,
+        Rect::new(7, 0, 13, 10),
+        Scalar::all(255.),
+        -1,
+        opencv::imgproc::LINE_8,
+        0,
+    )
+    .expect("rectangle should not fail");
+    let i = ImageContainer::new_from_mat(image).expect("container creation should not fail");
+
+    let splitter = splitter_traits::VarianceSplitter::new(splitter_traits::BlindSplitter::new(2));
+    let split = splitter
+        .split(&i.to_image_container_split())
+        .expect("split should not fail");
+
+    assert_eq!(split, Some(Split::Binary(CutDirection::CutParallelToY, 7)));
+
+    // the cut is moved to the closest position that leaves 8 pixels on each side
+    let splitter = splitter_traits::VarianceSplitter::new(splitter_traits::BlindSplitter::new(8));
+    let split = splitter
+        .split(&i.to_image_container_split())
+        .expect("split should not fail");
+
+    assert_eq!(split, Some(Split::Binary(CutDirection::CutParallelToY, 8)));
+}
+
+#[test]
+fn test_max_delta_splitter_ignores_the_outside_of_the_split() {
+    // black image with the right half white
+    let mut image = Mat::new_rows_cols_with_default(20, 20, CV_8UC3, Scalar::all(0.))
+        .expect("mat creation should not fail");
+    opencv::imgproc::rectangle(
+        &mut image,
+        Rect::new(10, 0, 10, 20),
+        Scalar::all(255.),
+        -1,
+        opencv::imgproc::LINE_8,
+        0,
+    )
+    .expect("rectangle should not fail");
+    let i = ImageContainer::new_from_mat(image).expect("container creation should not fail");
+    let [black, _] = i
+        .to_image_container_split()
+        .split(CutDirection::CutParallelToY, 10)
+        .expect("split should not fail");
+
+    // the white pixels next to the black half must not leak in the blur
+    let splitter = splitter_traits::MaxDeltaSplitter::new(2, 10.);
+    let split = splitter.split(&black).expect("split should not fail");
+    assert_eq!(split, None);
+
+    let split = splitter
+        .split(&i.to_image_container_split())
+        .expect("split should not fail");
+    assert!(split.is_some());
+}
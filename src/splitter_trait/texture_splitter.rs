@@ -0,0 +1,71 @@
+use opencv::core::Rect;
+
+use super::*;
+
+/// splitter that compares the two halves that the `BlindSplitter` would generate, and splits
+/// only if they have a different texture (local binary pattern histograms) or mean color.
+/// Unlike the std based splitters, uniformly textured areas are considered homogeneous
+pub struct TextureSplitter {
+    texture_distance_threshold: f64,
+    color_distance_threshold: f64,
+    blind_splitter: BlindSplitter,
+}
+
+impl TextureSplitter {
+    pub fn new(
+        min_split_size: i32,
+        texture_distance_threshold: f64,
+        color_distance_threshold: f64,
+    ) -> Self {
+        Self {
+            texture_distance_threshold,
+            color_distance_threshold,
+            blind_splitter: BlindSplitter::new(min_split_size),
+        }
+    }
+}
+
+impl SplitterTrait for TextureSplitter {
+    fn split(&self, image: &ImageContainerSplit<'_>) -> Result<Option<Split>> {
+        let split = match self.blind_splitter.split(image)? {
+            Some(e) => e,
+            None => return Ok(None),
+        };
+
+        let (x, y, w, h) = (image.x_start, image.y_start, image.width, image.height);
+        let [a, b] = match split {
+            Split::Binary(CutDirection::CutParallelToX, cut_at) => {
+                [Rect::new(x, y, w, cut_at), Rect::new(x, y + cut_at, w, h - cut_at)]
+            }
+            Split::Binary(CutDirection::CutParallelToY, cut_at) => {
+                [Rect::new(x, y, cut_at, h), Rect::new(x + cut_at, y, w - cut_at, h)]
+            }
+            Split::Quad { .. } => return Err(anyhow!("the blind splitter only does binary splits")),
+        };
+
+        let lbp_image = image.container.get_lbp_image()?;
+        let delta_texture = TextureHistogram::new_from_lbp_image(lbp_image, a)?
+            .chi_square_distance(&TextureHistogram::new_from_lbp_image(lbp_image, b)?);
+
+        let integral_image = &image.container.integral_image;
+        let delta_color = (integral_image.get_statistics(a)?.mean()
+            - integral_image.get_statistics(b)?.mean())
+        .as_slice()
+        .iter()
+        .map(|x| x.powi(2))
+        .sum::<f64>()
+        .sqrt();
+
+        if delta_texture > self.texture_distance_threshold
+            || delta_color > self.color_distance_threshold
+        {
+            return Ok(Some(split));
+        } else {
+            return Ok(None);
+        }
+    }
+
+    fn min_split_size(&self) -> i32 {
+        return self.blind_splitter.min_split_size();
+    }
+}
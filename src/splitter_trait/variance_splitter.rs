@@ -0,0 +1,66 @@
+use opencv::core::Rect;
+
+use super::*;
+
+/// splitter that cuts at the row or column that minimizes the sum of the variances of the two
+/// resulting areas (similarly to the Otsu method).
+/// The decision of whether to split or not is delegated to the `decision_splitter`, and the cut
+/// falls at least `min_split_size` pixels away from the borders (when that is not possible the
+/// cut of the decision splitter is used)
+pub struct VarianceSplitter<T: SplitterTrait> {
+    decision_splitter: T,
+}
+
+impl<T: SplitterTrait> VarianceSplitter<T> {
+    pub fn new(decision_splitter: T) -> Self {
+        Self { decision_splitter }
+    }
+}
+
+impl<T: SplitterTrait> SplitterTrait for VarianceSplitter<T> {
+    fn split(&self, image: &ImageContainerSplit<'_>) -> Result<Option<Split>> {
+        let original_split = match self.decision_splitter.split(image)? {
+            Some(e) => e,
+            None => return Ok(None),
+        };
+
+        // with the integral images of the container every candidate cut is evaluated in O(1)
+        let integral_image = &image.container.integral_image;
+        let (x, y, w, h) = (image.x_start, image.y_start, image.width, image.height);
+
+        // no area smaller than the minimum size of the decision splitter is created
+        let min_size = self.decision_splitter.min_split_size().max(1);
+        let mut candidates = Vec::with_capacity((w + h) as usize);
+        for cut_at in min_size..=h - min_size {
+            candidates.push((
+                CutDirection::CutParallelToX,
+                cut_at,
+                [Rect::new(x, y, w, cut_at), Rect::new(x, y + cut_at, w, h - cut_at)],
+            ));
+        }
+        for cut_at in min_size..=w - min_size {
+            candidates.push((
+                CutDirection::CutParallelToY,
+                cut_at,
+                [Rect::new(x, y, cut_at, h), Rect::new(x + cut_at, y, w - cut_at, h)],
+            ));
+        }
+
+        let mut best_cut = None;
+        let mut best_cost = f64::INFINITY;
+        for (direction, cut_at, [a, b]) in candidates {
+            let cost = integral_image.get_statistics(a)?.sum_of_squared_errors()
+                + integral_image.get_statistics(b)?.sum_of_squared_errors();
+            if cost < best_cost {
+                best_cost = cost;
+                best_cut = Some(Split::Binary(direction, cut_at));
+            }
+        }
+
+        return Ok(Some(best_cut.unwrap_or(original_split)));
+    }
+
+    fn min_split_size(&self) -> i32 {
+        return self.decision_splitter.min_split_size();
+    }
+}
@@ -0,0 +1,4 @@
+#[cfg(test)]
+mod test;
+mod worker_pool;
+pub use worker_pool::*;
@@ -0,0 +1,34 @@
+use crate::prelude::*;
+
+#[test]
+fn test_empty_pool_is_an_error() {
+    let _ = WorkerPool::new(0).err().expect("a pool without workers should not be created");
+}
+
+#[test]
+fn test_panic_message_is_propagated() {
+    let pool = WorkerPool::new(2).expect("worker pool creation should not fail");
+    let items = vec![1, 2, 3];
+
+    let results = pool
+        .parallel_map(2, &items, |x| x * 2)
+        .expect("parallel map should not fail");
+    assert_eq!(results, vec![2, 4, 6]);
+
+    let error = pool
+        .parallel_map(2, &items, |x| {
+            if *x == 2 {
+                panic!("item {x} is not valid");
+            }
+            x * 2
+        })
+        .err()
+        .expect("parallel map should fail");
+    assert!(error.to_string().contains("item 2 is not valid"));
+
+    // the pool is still usable after a job has panicked
+    let handle = pool.execute(|| -> i32 { panic!("static message") });
+    let error = handle.join().err().expect("join should fail");
+    assert!(error.to_string().contains("static message"));
+    assert_eq!(pool.execute(|| 42).join().expect("join should not fail"), 42);
+}
@@ -0,0 +1,129 @@
+use std::{
+    any::Any,
+    panic::{catch_unwind, AssertUnwindSafe},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        mpsc::{channel, Receiver},
+    },
+    thread,
+};
+
+use anyhow::Context;
+use rayon_core::{ThreadPool, ThreadPoolBuilder};
+
+use crate::prelude::*;
+
+/// a set of threads that is spawned once and then reused by both the split and the merge phase,
+/// and by any number of `MainLogic` executed one after the other.
+/// The jobs of the phases borrow the splitter, the merger and the image views trough the scopes
+/// of `rayon_core`, so no lifetime has to be erased
+pub struct WorkerPool {
+    pool: ThreadPool,
+}
+
+impl WorkerPool {
+    pub fn new(num_of_workers: usize) -> Result<Self> {
+        if num_of_workers == 0 {
+            return Err(anyhow!("a worker pool must have at least one worker"));
+        }
+
+        let pool = ThreadPoolBuilder::new()
+            .num_threads(num_of_workers)
+            .thread_name(|i| format!("worker {i}"))
+            .build()
+            .context("unable to spawn the workers of the pool")?;
+        return Ok(Self { pool });
+    }
+
+    pub fn num_of_workers(&self) -> usize {
+        return self.pool.current_num_threads();
+    }
+
+    /// queue a job for execution on one of the workers
+    pub fn execute<T, F>(&self, job: F) -> JobHandle<T>
+    where
+        T: Send + 'static,
+        F: FnOnce() -> T + Send + 'static,
+    {
+        let (result_tx, result_rx) = channel();
+        self.pool.spawn(move || {
+            let _ = result_tx.send(catch_unwind(AssertUnwindSafe(job)));
+        });
+        return JobHandle { result_rx };
+    }
+
+    /// apply `job` to all the `items` using at most `num_of_workers` workers of the pool.
+    /// Every worker keeps picking the next item that has not been processed yet, so the load is
+    /// balanced even when some items are much slower than others.
+    /// The results are returned in the same order as the items, if `job` panics the panic
+    /// message is returned as an error once all the workers have stopped
+    pub fn parallel_map<T, R, F>(&self, num_of_workers: usize, items: &[T], job: F) -> Result<Vec<R>>
+    where
+        T: Sync,
+        R: Send,
+        F: Fn(&T) -> R + Sync,
+    {
+        let num_of_workers = num_of_workers.clamp(1, self.num_of_workers());
+        let next_item = AtomicUsize::new(0);
+        let (job, next_item) = (&job, &next_item);
+
+        let (result_tx, result_rx) = channel();
+        // the scope returns only once all the jobs spawned inside it have completed
+        self.pool.scope(|scope| {
+            for _ in 0..num_of_workers {
+                let result_tx = result_tx.clone();
+                scope.spawn(move |_| {
+                    let worker_results = catch_unwind(AssertUnwindSafe(|| {
+                        let mut results = Vec::new();
+                        loop {
+                            let index = next_item.fetch_add(1, Ordering::Relaxed);
+                            if index >= items.len() {
+                                return results;
+                            }
+                            results.push((index, job(&items[index])));
+                        }
+                    }));
+                    let _ = result_tx.send(worker_results);
+                });
+            }
+        });
+        drop(result_tx);
+
+        let mut results = Vec::with_capacity(items.len());
+        for worker_results in result_rx {
+            let worker_results = worker_results
+                .map_err(|payload| anyhow!("job has panicked: {}", panic_message(&*payload)))?;
+            results.extend(worker_results);
+        }
+        results.sort_by_key(|(index, _)| *index);
+        return Ok(results.into_iter().map(|(_, result)| result).collect());
+    }
+}
+
+/// handle to the result of a job queued on a WorkerPool
+pub struct JobHandle<T> {
+    result_rx: Receiver<thread::Result<T>>,
+}
+
+impl<T> JobHandle<T> {
+    /// wait for the job to complete, an error with the panic message is returned if the job has
+    /// panicked
+    pub fn join(self) -> Result<T> {
+        return match self.result_rx.recv() {
+            Ok(Ok(result)) => Ok(result),
+            Ok(Err(payload)) => Err(anyhow!("job has panicked: {}", panic_message(&*payload))),
+            Err(_) => Err(anyhow!("job has been dropped without being executed")),
+        };
+    }
+}
+
+/// the message given to `panic!`, if there is one
+fn panic_message(payload: &(dyn Any + Send)) -> &str {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        return message;
+    }
+    if let Some(message) = payload.downcast_ref::<String>() {
+        return message;
+    }
+    return "unknown panic payload";
+}